@@ -0,0 +1,491 @@
+//! Caching of responses keyed on the content of their request.
+//!
+//! A [`ResponseCache`] is configured on the [`Client`](crate::client::Client) and serves repeated
+//! requests of [`Client::create`](crate::client::Client::create) without sending them again.
+//! A request is identified by its method, its url, its model and its canonical JSON body,
+//! so requests which only differ in the order of their fields share a response.
+//!
+//! Only endpoints whose response is a pure function of the request are cached, e.g. a `Search`
+//! or a `Completion` with a `temperature` of zero. Requests with sampling settings which make the
+//! response non-deterministic bypass the cache unless it is [forced](ResponseCache::force),
+//! and endpoints with side effects like file uploads are never cached.
+//!
+//! # Example
+//! ```
+//! use hyper::StatusCode;
+//! use openai_rs::cache::ResponseCache;
+//! use openai_rs::endpoints::completion::Completion;
+//! use openai_rs::openai;
+//! use openai_rs::transport::{MockResponse, MockTransport};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let completion = serde_json::json!({
+//!     "id": "cmpl-1", "object": "text_completion", "created": 0, "model": "davinci",
+//!     "choices": [{ "text": "4", "index": 0, "logprobs": null, "finish_reason": "stop" }]
+//! });
+//! let transport = MockTransport::new();
+//! transport.push(MockResponse::json(StatusCode::OK, &completion))
+//!     .push(MockResponse::json(StatusCode::OK, &completion));
+//!
+//! let client = openai::builder("api_key")
+//!     .transport(transport.clone())
+//!     .cache(ResponseCache::memory(1_000))
+//!     .build()
+//!     .unwrap();
+//!
+//! let deterministic = Completion { temperature: 0., ..Default::default() };
+//! client.create(Some("davinci"), &deterministic).await.unwrap();
+//! client.create(Some("davinci"), &deterministic).await.unwrap();
+//! assert_eq!(transport.requests().len(), 1);
+//!
+//! // The default temperature samples the response, so it is requested every time.
+//! client.create(Some("davinci"), &Completion::default()).await.unwrap();
+//! assert_eq!(transport.requests().len(), 2);
+//! # }
+//! ```
+//!
+//! # Moderation
+//! A cached response is served before the prompt is moderated by the gate of the client, as the
+//! gate is part of the key: its prompt already passed the same gate when it was stored, and
+//! responses stored without a gate or with another one are never served to the client.
+//!
+//! ```
+//! use std::borrow::Cow;
+//! use hyper::StatusCode;
+//! use openai_rs::cache::ResponseCache;
+//! use openai_rs::endpoints::completion::Completion;
+//! use openai_rs::endpoints::moderations::ModerationGate;
+//! use openai_rs::endpoints::ResponseError;
+//! use openai_rs::openai;
+//! use openai_rs::transport::{MockResponse, MockTransport};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let cache = ResponseCache::disk(std::env::temp_dir().join("openai-rs-cache-moderation"));
+//! cache.clear().await;
+//! let completion = Completion {
+//!     prompt: Some(Cow::Borrowed("I want to hurt them.")),
+//!     temperature: 0.,
+//!     ..Default::default()
+//! };
+//!
+//! // A client without a gate stores the response.
+//! let transport = MockTransport::new();
+//! transport.push(MockResponse::json(StatusCode::OK, &serde_json::json!({
+//!     "id": "cmpl-1", "object": "text_completion", "created": 0, "model": "davinci",
+//!     "choices": [{ "text": "...", "index": 0, "logprobs": null, "finish_reason": "stop" }]
+//! })));
+//! let client = openai::builder("api_key").transport(transport).cache(cache.clone()).build().unwrap();
+//! client.create(Some("davinci"), &completion).await.unwrap();
+//!
+//! // A gated client sharing the directory moderates the prompt instead of serving the response.
+//! let transport = MockTransport::new();
+//! transport.push(MockResponse::json(StatusCode::OK, &serde_json::json!({
+//!     "id": "modr-1", "model": "omni-moderation-latest",
+//!     "results": [{ "flagged": true, "categories": { "violence": true }, "category_scores": { "violence": 0.97 } }]
+//! })));
+//! let client = openai::builder("api_key")
+//!     .transport(transport.clone())
+//!     .moderation_gate(ModerationGate::default())
+//!     .cache(ResponseCache::disk(std::env::temp_dir().join("openai-rs-cache-moderation")))
+//!     .build()
+//!     .unwrap();
+//! let response = client.create(Some("davinci"), &completion).await;
+//! assert!(matches!(response, Err(ResponseError::Flagged(_))));
+//! assert_eq!(transport.requests().len(), 1);
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::future::{ready, Future};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use hyper::body::Bytes;
+use serde::{Deserialize, Serialize};
+use crate::endpoints::moderations::ModerationGate;
+use crate::endpoints::request::{HttpEndpoint, RequestBody};
+use crate::endpoints::ResponseError;
+use crate::transport::CassetteBody;
+
+/// Whether the response of a request may be served from a [`ResponseCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Caching {
+    /// The request has side effects or a changing response and is never cached.
+    Never,
+
+    /// The response is determined by the request, e.g. a completion with a temperature of zero.
+    Deterministic,
+
+    /// The response is sampled, so it is only cached if the cache is forced.
+    Sampled,
+}
+
+impl Caching {
+    /// Returns [`Caching::Deterministic`] if the sampling settings are deterministic,
+    /// [`Caching::Sampled`] otherwise.
+    pub fn deterministic_if(deterministic: bool) -> Self {
+        if deterministic {
+            Self::Deterministic
+        } else {
+            Self::Sampled
+        }
+    }
+}
+
+/// A cached response body with the time it was stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub body: Bytes,
+    pub stored_at: SystemTime,
+}
+
+/// The future of an operation of a [`CacheBackend`].
+pub type CacheFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The storage of a [`ResponseCache`]. Backends do not fail: an entry which cannot be
+/// read is a miss and an entry which cannot be written is dropped, both with a warning.
+///
+/// The operations are awaited by [`Client::create`](crate::client::Client::create), so backends
+/// doing I/O must not block the runtime while they wait for it.
+pub trait CacheBackend: Debug + Send + Sync {
+    /// Returns the entry of the key, if any.
+    fn get(&self, key: &str) -> CacheFuture<'_, Option<CacheEntry>>;
+
+    /// Stores the entry under the key, replacing a previous one.
+    fn insert(&self, key: &str, entry: CacheEntry) -> CacheFuture<'_, ()>;
+
+    /// Removes the entry of the key, if any.
+    fn remove(&self, key: &str) -> CacheFuture<'_, ()>;
+
+    /// Removes all entries.
+    fn clear(&self) -> CacheFuture<'_, ()>;
+}
+
+/// A cache of response bodies in front of [`Client::create`](crate::client::Client::create).
+/// Clones share the same backend.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    backend: Arc<dyn CacheBackend>,
+    ttl: Option<Duration>,
+    force: bool,
+}
+
+impl ResponseCache {
+    /// Returns a cache which stores its entries in the backend and never expires them.
+    pub fn new<B: CacheBackend + 'static>(backend: B) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            ttl: None,
+            force: false,
+        }
+    }
+
+    /// Returns a cache which keeps the `capacity` most recently used entries in memory.
+    pub fn memory(capacity: usize) -> Self {
+        Self::new(MemoryCache::new(capacity))
+    }
+
+    /// Returns a cache which stores an entry per file in the directory, so it is kept across runs.
+    pub fn disk(dir: impl Into<PathBuf>) -> Self {
+        Self::new(DiskCache::new(dir))
+    }
+
+    /// Sets the time after which an entry expires and the request is sent again.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets whether requests with sampled responses are cached as well, e.g. to replay
+    /// one sample of a `temperature: 0.7` completion during development.
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Returns the backend the entries are stored in.
+    pub fn backend(&self) -> &dyn CacheBackend {
+        &*self.backend
+    }
+
+    /// Returns the body cached under the key, unless it has expired.
+    pub async fn get(&self, key: &str) -> Option<Bytes> {
+        let entry = self.backend.get(key).await?;
+        let age = entry.stored_at.elapsed().unwrap_or_default();
+        if self.ttl.is_some_and(|ttl| age > ttl) {
+            self.backend.remove(key).await;
+            return None;
+        }
+
+        Some(entry.body)
+    }
+
+    /// Caches the body under the key.
+    pub async fn insert(&self, key: &str, body: Bytes) {
+        self.backend.insert(key, CacheEntry {
+            body,
+            stored_at: SystemTime::now(),
+        }).await;
+    }
+
+    /// Removes all entries of the cache.
+    pub async fn clear(&self) {
+        self.backend.clear().await;
+    }
+
+    /// Returns the key of the request, or `None` if the request bypasses the cache.
+    /// The key is the hex encoded SHA-256 of the method, the url, the model, the canonical body
+    /// and the moderation gate of the client, so a response is only served to clients whose gate
+    /// the prompt passed when the response was stored.
+    pub(crate) fn key<T: HttpEndpoint>(
+        &self,
+        api_url: &str,
+        engine_id: Option<&str>,
+        model: &T,
+        gate: Option<&ModerationGate>
+    ) -> Result<Option<String>, ResponseError> {
+        match model.caching() {
+            Caching::Never => return Ok(None),
+            Caching::Sampled if !self.force => return Ok(None),
+            _ => {}
+        }
+
+        // The keys of a `serde_json::Map` are sorted, which makes the serialized body canonical.
+        let body = match model.body()? {
+            RequestBody::Json(body) => serde_json::from_str::<serde_json::Value>(&body)?,
+            _ => return Ok(None),
+        };
+        let model_name = body.get("model").and_then(serde_json::Value::as_str)
+            .or(engine_id)
+            .unwrap_or_default();
+
+        let gate = match gate {
+            Some(gate) => {
+                let mut categories = gate.categories.clone();
+                categories.sort();
+                format!("moderated by {} in {}", gate.model.as_deref().unwrap_or_default(), categories.join(","))
+            },
+            None => "unmoderated".to_owned()
+        };
+
+        let content = format!(
+            "{} {}{}\n{}\n{}\n{}",
+            model.method(), api_url, model.path(engine_id)?, model_name, body, gate
+        );
+        let digest = openssl::sha::sha256(content.as_bytes());
+        Ok(Some(digest.iter().map(|byte| format!("{:02x}", byte)).collect()))
+    }
+}
+
+/// An in-memory backend which evicts the least recently used entry once it is full.
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<Lru>,
+}
+
+/// The entries of a [`MemoryCache`] with the tick of their last use.
+#[derive(Debug, Default)]
+struct Lru {
+    entries: HashMap<String, (CacheEntry, u64)>,
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl MemoryCache {
+    /// Returns an empty cache which holds at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Returns the number of entries in the cache.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// Returns whether the cache has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Lru {
+    fn get(&mut self, key: &str) -> Option<CacheEntry> {
+        let (entry, used) = self.entries.get_mut(key)?;
+
+        self.order.remove(used);
+        self.tick += 1;
+        *used = self.tick;
+        self.order.insert(self.tick, key.to_owned());
+        Some(entry.clone())
+    }
+
+    fn insert(&mut self, key: &str, entry: CacheEntry, capacity: usize) {
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(key.to_owned(), (entry, self.tick)) {
+            self.order.remove(&used);
+        }
+        self.order.insert(self.tick, key.to_owned());
+
+        while self.entries.len() > capacity {
+            match self.order.pop_first() {
+                Some((_, evicted)) => self.entries.remove(&evicted),
+                None => break
+            };
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some((_, used)) = self.entries.remove(key) {
+            self.order.remove(&used);
+        }
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    fn get(&self, key: &str) -> CacheFuture<'_, Option<CacheEntry>> {
+        Box::pin(ready(self.state.lock().unwrap().get(key)))
+    }
+
+    fn insert(&self, key: &str, entry: CacheEntry) -> CacheFuture<'_, ()> {
+        self.state.lock().unwrap().insert(key, entry, self.capacity);
+        Box::pin(ready(()))
+    }
+
+    fn remove(&self, key: &str) -> CacheFuture<'_, ()> {
+        self.state.lock().unwrap().remove(key);
+        Box::pin(ready(()))
+    }
+
+    fn clear(&self) -> CacheFuture<'_, ()> {
+        *self.state.lock().unwrap() = Lru::default();
+        Box::pin(ready(()))
+    }
+}
+
+/// The number of temporary files of the [`DiskCache`]s of the process, which makes their names unique.
+static TEMPORARY_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// A backend which stores each entry as a JSON file named by its key in a directory,
+/// which is created with the first entry.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+/// The file of an entry of a [`DiskCache`].
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    /// The seconds since the unix epoch when the entry was stored.
+    stored_at: u64,
+    #[serde(flatten)]
+    body: CassetteBody,
+}
+
+impl DiskCache {
+    /// Returns a cache which stores its entries in the directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    async fn read(&self, key: &str) -> Option<CacheEntry> {
+        let path = self.path(key);
+        let content = match tokio::fs::read(&path).await {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return None,
+            Err(error) => {
+                warn!("Failed to read cache entry {}: {}", path.display(), error);
+                return None;
+            }
+        };
+
+        match serde_json::from_slice::<DiskEntry>(&content) {
+            Ok(entry) => Some(CacheEntry {
+                body: entry.body.to_bytes(),
+                stored_at: UNIX_EPOCH + Duration::from_secs(entry.stored_at),
+            }),
+            Err(error) => {
+                warn!("Malformed cache entry {}: {}", path.display(), error);
+                None
+            }
+        }
+    }
+
+    async fn write(&self, key: &str, entry: &CacheEntry) -> Result<(), ResponseError> {
+        let stored_at = entry.stored_at.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let content = serde_json::to_vec(&DiskEntry {
+            stored_at: stored_at.as_secs(),
+            body: CassetteBody::new(&entry.body),
+        })?;
+
+        // Writes to a temporary file first, so that concurrent readers never see a partial entry.
+        // Its name is unique to the write, so concurrent writers of the same key never share it.
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let counter = TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed);
+        let temporary = self.dir.join(format!("{}.json.{}-{}.tmp", key, std::process::id(), counter));
+        tokio::fs::write(&temporary, content).await?;
+        if let Err(error) = tokio::fs::rename(&temporary, self.path(key)).await {
+            let _ = tokio::fs::remove_file(&temporary).await;
+            return Err(error.into());
+        }
+        Ok(())
+    }
+
+    async fn remove_all(&self) -> std::io::Result<()> {
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CacheBackend for DiskCache {
+    fn get(&self, key: &str) -> CacheFuture<'_, Option<CacheEntry>> {
+        let key = key.to_owned();
+        Box::pin(async move { self.read(&key).await })
+    }
+
+    fn insert(&self, key: &str, entry: CacheEntry) -> CacheFuture<'_, ()> {
+        let key = key.to_owned();
+        Box::pin(async move {
+            if let Err(error) = self.write(&key, &entry).await {
+                warn!("Failed to write cache entry {}: {}", self.path(&key).display(), error);
+            }
+        })
+    }
+
+    fn remove(&self, key: &str) -> CacheFuture<'_, ()> {
+        let path = self.path(key);
+        Box::pin(async move {
+            let _ = tokio::fs::remove_file(path).await;
+        })
+    }
+
+    fn clear(&self) -> CacheFuture<'_, ()> {
+        Box::pin(async move {
+            let _ = self.remove_all().await;
+        })
+    }
+}
@@ -1,34 +1,533 @@
-use hyper::Client as HyperClient;
+use std::borrow::Cow;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use futures_util::{stream, StreamExt};
+use hyper::{Body, Request, Response as HyperResponse};
+use hyper::body::Bytes;
 use hyper::client::HttpConnector;
+use hyper::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
 use hyper_openssl::HttpsConnector;
-use crate::endpoints::{Response, ResponseError};
-use crate::endpoints::request::Endpoint;
+use openssl::ssl::{SslConnector, SslMethod};
+use crate::cache::ResponseCache;
+use crate::endpoints::{ApiError, ResponseError, UsageReport};
+use crate::endpoints::moderations::{Moderation, ModerationGate, ModerationInput, ModerationResponse};
+use crate::endpoints::request::{HttpEndpoint, Streaming};
+use crate::endpoints::RateLimit;
+use crate::openai::{DEFAULT_API_ROOT, DEFAULT_BASE_URL};
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+use crate::stream::{byte_stream, is_event_stream, ByteStream, EventStream};
+use crate::transport::{cassette_transport, CassetteMode, HyperTransport, Transport};
+use crate::usage::{UsageTracker, DEFAULT_TENANT};
 
-pub(crate) type HttpsHyperClient = HyperClient<HttpsConnector<HttpConnector>>;
+/// The user agent which is sent if none is configured on the [`ClientBuilder`].
+pub const DEFAULT_USER_AGENT: &str = concat!("openai-rs/", env!("CARGO_PKG_VERSION"));
 
-#[derive(Debug)]
+/// The header carrying the organization id of a request.
+pub const ORGANIZATION_HEADER: &str = "openai-organization";
+
+#[derive(Debug, Clone)]
 pub struct Client {
     pub(crate) api_key: String,
-    pub(crate) https: HttpsHyperClient,
+    pub(crate) transport: Arc<dyn Transport>,
+    pub(crate) base_url: String,
+    pub(crate) api_root: String,
+    pub(crate) headers: HeaderMap,
+    pub(crate) request_timeout: Option<Duration>,
+    pub(crate) read_timeout: Option<Duration>,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) usage: Arc<UsageTracker>,
+    pub(crate) tenant: String,
+    pub(crate) moderation: Option<ModerationGate>,
+    pub(crate) rate_limiter: Option<Arc<RateLimiter>>,
+    pub(crate) cache: Option<ResponseCache>,
+}
+
+/// The HTTP version(s) the client is allowed to use.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HttpVersion {
+    /// Negotiates HTTP/2 via ALPN and falls back to HTTP/1.1 if the server does not support it.
+    #[default]
+    Auto,
+
+    /// Only speaks HTTP/1.1, e.g. for proxies which do not support HTTP/2.
+    Http1Only,
+
+    /// Only speaks HTTP/2.
+    Http2Only,
+}
+
+/// The progress of [`Client::create_many_with_progress`], reported after each finished request.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The number of requests which finished, successfully or not.
+    pub completed: usize,
+
+    /// The number of requests which failed.
+    pub failed: usize,
+
+    /// The number of requests of the batch, if the iterator knows its exact length.
+    pub total: Option<usize>,
+}
+
+/// A builder to configure the [`Client`] and its underlying connection.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use openai_rs::client::{Client, HttpVersion};
+/// use openai_rs::endpoints::ResponseError;
+/// use openai_rs::openai;
+///
+/// let client: Result<Client, ResponseError> = openai::builder("api_key")
+///     .http_version(HttpVersion::Http1Only)
+///     .connect_timeout(Duration::from_secs(5))
+///     .request_timeout(Duration::from_secs(60))
+///     .organization("org-123")
+///     .user_agent("my-service/1.0")
+///     .build();
+///
+/// assert!(client.is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    api_key: String,
+    base_url: String,
+    api_root: String,
+    connect_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: usize,
+    http_version: HttpVersion,
+    user_agent: String,
+    organization: Option<String>,
+    headers: Vec<(String, String)>,
+    retry: RetryPolicy,
+    tenant: String,
+    transport: Option<Arc<dyn Transport>>,
+    moderation: Option<ModerationGate>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    cache: Option<ResponseCache>,
+    cassette: Option<(PathBuf, CassetteMode)>,
+}
+
+impl ClientBuilder {
+    /// Returns a new builder with the default configuration for the given API key.
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_owned(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            api_root: DEFAULT_API_ROOT.to_owned(),
+            connect_timeout: None,
+            request_timeout: None,
+            read_timeout: None,
+            pool_idle_timeout: Some(Duration::from_secs(10)),
+            pool_max_idle_per_host: usize::MAX,
+            http_version: HttpVersion::default(),
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            organization: None,
+            headers: Vec::new(),
+            retry: RetryPolicy::none(),
+            tenant: DEFAULT_TENANT.to_owned(),
+            transport: None,
+            moderation: None,
+            rate_limiter: None,
+            cache: None,
+            cassette: None,
+        }
+    }
+
+    /// Sets the base url every endpoint is requested against, see [`Client::with_base_url`].
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Sets the path prefix between base url and endpoint path, see [`Client::with_api_root`].
+    pub fn api_root(mut self, api_root: &str) -> Self {
+        self.api_root = normalize_api_root(api_root);
+        self
+    }
+
+    /// Sets the timeout for establishing a connection (including the TCP handshake).
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for sending a request until the response head has been received.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for reading the whole response body once the head has been received.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how long idle connections are kept in the pool. `None` keeps them forever.
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum number of idle connections which are kept per host.
+    pub fn pool_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.pool_max_idle_per_host = max_idle;
+        self
+    }
+
+    /// Sets the HTTP version(s) the client is allowed to use.
+    pub fn http_version(mut self, http_version: HttpVersion) -> Self {
+        self.http_version = http_version;
+        self
+    }
+
+    /// Sets the user agent which is sent with every request.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_owned();
+        self
+    }
+
+    /// Sets the organization id which is sent with every request.
+    pub fn organization(mut self, organization: &str) -> Self {
+        self.organization = Some(organization.to_owned());
+        self
+    }
+
+    /// Adds a header which is sent with every request.
+    /// Headers set by the endpoint itself (e.g. `Authorization`) take precedence.
+    pub fn default_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets the policy for retrying failed requests. By default requests are not retried.
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the tenant whose usage counter is increased by the requests of the client.
+    pub fn tenant(mut self, tenant: &str) -> Self {
+        self.tenant = tenant.to_owned();
+        self
+    }
+
+    /// Moderates the prompt of each request with the gate before it is sent, see [`ModerationGate`].
+    pub fn moderation_gate(mut self, gate: ModerationGate) -> Self {
+        self.moderation = Some(gate);
+        self
+    }
+
+    /// Limits the requests and tokens per minute of the client and all clients derived from it,
+    /// see [`RateLimiter`].
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use hyper::StatusCode;
+    /// use openai_rs::endpoints::edits::Edit;
+    /// use openai_rs::openai;
+    /// use openai_rs::rate_limit::RateLimiter;
+    /// use openai_rs::transport::{MockResponse, MockTransport};
+    /// use tokio::time::Instant;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let edit = serde_json::json!({ "object": "edit", "created": 0, "choices": [] });
+    /// let transport = MockTransport::new();
+    /// transport.push(MockResponse::json(StatusCode::OK, &edit)
+    ///     .header("x-ratelimit-remaining-requests", "0")
+    ///     .header("x-ratelimit-reset-requests", "100ms"));
+    /// transport.push(MockResponse::json(StatusCode::OK, &edit));
+    ///
+    /// let client = openai::builder("api_key")
+    ///     .transport(transport)
+    ///     .rate_limiter(RateLimiter::new().tokens_per_minute(90_000))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let start = Instant::now();
+    /// client.create(Some("text-davinci-edit-001"), &Edit::default()).await.unwrap();
+    /// // The second request is queued until the exhausted request limit resets.
+    /// client.create(Some("text-davinci-edit-001"), &Edit::default()).await.unwrap();
+    /// assert!(start.elapsed() >= Duration::from_millis(100));
+    /// # }
+    /// ```
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
+
+    /// Sets the cache which serves repeated requests of [`Client::create`] without sending them,
+    /// see [`ResponseCache`] for the requests which are cached.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use openai_rs::cache::ResponseCache;
+    /// use openai_rs::client::Client;
+    /// use openai_rs::openai;
+    ///
+    /// let client: Client = openai::builder("api_key")
+    ///     .cache(ResponseCache::disk("target/openai-cache").ttl(Duration::from_secs(24 * 60 * 60)))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Sets the transport the requests are sent through, e.g. a [`MockTransport`] in tests.
+    /// The connection options of the builder only apply to the default [`HyperTransport`].
+    ///
+    /// [`MockTransport`]: crate::transport::MockTransport
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Records the requests of the client with their responses to the cassette file, or replays
+    /// them from it without sending them, see [`ReplayTransport`] for an example.
+    /// The [`REDACTED_HEADERS`] and the default headers of the builder are redacted in the cassette.
+    /// Recording wraps the transport of the client, e.g. the default [`HyperTransport`].
+    ///
+    /// [`ReplayTransport`]: crate::transport::ReplayTransport
+    /// [`REDACTED_HEADERS`]: crate::transport::REDACTED_HEADERS
+    pub fn cassette(mut self, path: impl Into<PathBuf>, mode: CassetteMode) -> Self {
+        self.cassette = Some((path.into(), mode));
+        self
+    }
+
+    /// Builds the client. Fails if the TLS connector cannot be created, a header is malformed
+    /// or the cassette to replay cannot be read.
+    pub fn build(self) -> Result<Client, ResponseError> {
+        let invalid = |name: &str| ResponseError::InvalidHeader(name.to_owned());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_str(&self.user_agent)
+            .map_err(|_| invalid(USER_AGENT.as_str()))?);
+
+        if let Some(organization) = &self.organization {
+            headers.insert(
+                HeaderName::from_static(ORGANIZATION_HEADER),
+                HeaderValue::from_str(organization).map_err(|_| invalid(ORGANIZATION_HEADER))?
+            );
+        }
+
+        for (name, value) in &self.headers {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid(name))?,
+                HeaderValue::from_str(value).map_err(|_| invalid(name))?
+            );
+        }
+
+        let mut transport = match &self.transport {
+            Some(transport) => transport.clone(),
+            None => Arc::new(self.hyper_transport()?)
+        };
+        if let Some((path, mode)) = &self.cassette {
+            let redacted: Vec<String> = self.headers.iter().map(|(name, _)| name.to_ascii_lowercase()).collect();
+            transport = cassette_transport(transport, path, *mode, &redacted)?;
+        }
+
+        Ok(Client {
+            api_key: self.api_key,
+            transport,
+            base_url: self.base_url,
+            api_root: self.api_root,
+            headers,
+            request_timeout: self.request_timeout,
+            read_timeout: self.read_timeout,
+            retry: self.retry,
+            usage: Arc::new(UsageTracker::default()),
+            tenant: self.tenant,
+            moderation: self.moderation,
+            rate_limiter: self.rate_limiter,
+            cache: self.cache,
+        })
+    }
+
+    /// Returns the default transport configured by the connection options of the builder.
+    fn hyper_transport(&self) -> Result<HyperTransport, ResponseError> {
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_connect_timeout(self.connect_timeout);
+
+        let mut ssl = SslConnector::builder(SslMethod::tls())?;
+        match self.http_version {
+            HttpVersion::Auto => ssl.set_alpn_protos(b"\x02h2\x08http/1.1")?,
+            HttpVersion::Http1Only => ssl.set_alpn_protos(b"\x08http/1.1")?,
+            HttpVersion::Http2Only => ssl.set_alpn_protos(b"\x02h2")?,
+        }
+
+        let client = hyper::Client::builder()
+            .http2_only(self.http_version == HttpVersion::Http2Only)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .build(HttpsConnector::with_connector(http, ssl)?);
+
+        Ok(HyperTransport::new(client))
+    }
 }
 
 impl Client {
+    /// Sets the base url every endpoint is requested against, e.g. `http://localhost:8080`.
+    /// This allows to use a local stand-in server, a gateway or an OpenAI-compatible server.
+    ///
+    /// # Example
+    /// ```
+    /// use openai_rs::client::Client;
+    /// use openai_rs::openai;
+    ///
+    /// let client: Client = openai::new("api_key")
+    ///     .with_base_url("http://localhost:8080");
+    ///
+    /// assert_eq!(client.api_url(), "http://localhost:8080/v1");
+    /// ```
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Sets the path prefix which is put between the base url and the endpoint path.
+    /// By default this is `/v1`, an empty api root requests the endpoints on the base url directly.
+    ///
+    /// # Example
+    /// ```
+    /// use openai_rs::client::Client;
+    /// use openai_rs::openai;
+    ///
+    /// let client: Client = openai::new("api_key")
+    ///     .with_base_url("https://gateway.internal")
+    ///     .with_api_root("/openai/v1/");
+    ///
+    /// assert_eq!(client.api_url(), "https://gateway.internal/openai/v1");
+    /// ```
+    pub fn with_api_root(mut self, api_root: &str) -> Self {
+        self.api_root = normalize_api_root(api_root);
+        self
+    }
+
+    /// Sets the policy for retrying failed requests, see [`RetryPolicy`].
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the transport the requests are sent through, see [`ClientBuilder::transport`].
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Sets the gate which moderates the prompt of each request before it is sent,
+    /// `None` sends requests without moderation.
+    pub fn with_moderation_gate(mut self, gate: Option<ModerationGate>) -> Self {
+        self.moderation = gate;
+        self
+    }
+
+    /// Sets the limiter of the requests and tokens per minute, `None` sends requests without limits.
+    /// Clients derived from this client afterwards share the limiter.
+    pub fn with_rate_limiter(mut self, rate_limiter: Option<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter.map(Arc::new);
+        self
+    }
+
+    /// Sets the cache of the responses, `None` sends every request.
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Returns the cache of the responses, if any.
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_ref()
+    }
+
+    /// Returns a client for the given tenant, which shares the connection pool and
+    /// the usage tracker with this client but counts its usage separately.
+    pub fn with_tenant(&self, tenant: &str) -> Self {
+        Self {
+            tenant: tenant.to_owned(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the tenant whose usage counter is increased by the requests of the client.
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    /// Returns the cumulative token usage of all tenants of the client.
+    pub fn usage(&self) -> &UsageTracker {
+        &self.usage
+    }
+
+    /// Returns the base url of the client.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the api root of the client.
+    pub fn api_root(&self) -> &str {
+        &self.api_root
+    }
+
+    /// Returns the url every endpoint path is joined against (base url and api root).
+    pub fn api_url(&self) -> String {
+        format!("{}{}", self.base_url, self.api_root)
+    }
+
     /// Returns a new response from the OpenAI API.
     ///
     /// # Arguments
     ///
     /// * `engine_id` - The engine id to use. Due to few endpoints this can be optional.
-    /// * `model` - The model to use. Each Model in the endpoints module is a corresponding model.
+    /// * `model` - The model to use. Each struct in the endpoints module is a corresponding endpoint.
+    ///
+    /// The response is deserialized into the `Output` of the endpoint, e.g.
+    /// [`EditResponse`](crate::endpoints::edits::EditResponse) for an `Edit`.
+    ///
+    /// # Errors
+    /// Besides failures of the transport and the API, the request itself is rejected before it is sent
+    /// with [`ResponseError::MissingEngine`] if the endpoint is scoped to an engine but `engine_id`
+    /// is `None`, and with [`ResponseError::InvalidHeader`] if the api key is not a valid header value:
+    /// ```
+    /// use openai_rs::endpoints::edits::Edit;
+    /// use openai_rs::endpoints::ResponseError;
+    /// use openai_rs::openai;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let client = openai::new("api_key");
+    /// let response = client.create(None, &Edit::default()).await;
+    /// assert!(matches!(response, Err(ResponseError::MissingEngine(_))));
+    ///
+    /// let client = openai::new("api_key\n");
+    /// let response = client.create(Some("text-davinci-edit-001"), &Edit::default()).await;
+    /// assert!(matches!(response, Err(ResponseError::InvalidHeader(_))));
+    /// # }
+    /// ```
     ///
     /// # Example
     ///
     /// ```
     /// use std::borrow::Cow;
     /// use openai_rs::client::Client;
-    /// use openai_rs::endpoints::edits::Edit;
-    /// use openai_rs::endpoints::{Response, ResponseError};
+    /// use openai_rs::endpoints::edits::{Edit, EditResponse};
+    /// use openai_rs::endpoints::ResponseError;
     /// use openai_rs::openai;
     ///
+    /// # async fn example() {
     /// // Create the Client with your API key.
     /// let client: Client = openai::new("api_key");
     ///
@@ -40,30 +539,406 @@ impl Client {
     ///  };
     ///
     /// // Send the request to the OpenAI API.
-    /// let response: Result<Response, ResponseError> = client.create(
+    /// let response: Result<EditResponse, ResponseError> = client.create(
     ///     Some("text-davinci-edit-001"), &edit
     /// ).await;
+    /// # }
     /// ```
     pub async fn create<T>(
         &self,
         engine_id: Option<&str>,
         model: &T
-    ) -> Result<Response, ResponseError>
-        where T: Endpoint {
-        match self.https.request(model.request(&*self.api_key, engine_id)).await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    let body = hyper::body::to_bytes(response.into_body()).await?;
-                    let deserialized = serde_json::from_slice(&body)
-                        .map_err(ResponseError::from)?;
-                    trace!("Requesting: {:#?}", deserialized);
-
-                    Ok(deserialized)
-                } else {
-                    Err(ResponseError::ErrorCode(response.status()))
+    ) -> Result<T::Output, ResponseError>
+        where T: HttpEndpoint {
+        let key = match &self.cache {
+            Some(cache) => cache.key(&self.api_url(), engine_id, model, self.moderation.as_ref())?,
+            None => None
+        };
+
+        let cached = match (&self.cache, &key) {
+            (Some(cache), Some(key)) => cache.get(key).await,
+            _ => None
+        };
+        if let Some(body) = cached {
+            // A cached response was paid for when it was stored, so its usage is not recorded again.
+            debug!("Serving {} from the response cache", model.path(engine_id)?);
+            return T::parse(body);
+        }
+
+        self.moderate(model).await?;
+        let body = self.execute(engine_id, model).await?;
+        let deserialized = T::parse(body.clone())?;
+        trace!("Requesting: {:#?}", deserialized);
+
+        if let (Some(cache), Some(key)) = (&self.cache, &key) {
+            cache.insert(key, body).await;
+        }
+
+        if let Some(usage) = deserialized.usage() {
+            self.usage.record(&self.tenant, usage);
+        }
+
+        Ok(deserialized)
+    }
+
+    /// Returns the responses of many requests of the endpoint, which are sent with at most `concurrency`
+    /// requests in flight. The responses are in the order of the requests, and a failed request
+    /// only fails its own item instead of the whole batch.
+    ///
+    /// # Example
+    /// ```
+    /// use std::borrow::Cow;
+    /// use openai_rs::client::Client;
+    /// use openai_rs::endpoints::completion::Completion;
+    /// use openai_rs::openai;
+    ///
+    /// # async fn example() {
+    /// let client: Client = openai::new("api_key");
+    ///
+    /// let tickets = ["My order did not arrive.", "How do I reset my password?"];
+    /// let completions = tickets.iter().map(|ticket| Completion {
+    ///     prompt: Some(Cow::Owned(format!("Summarize the ticket: {}", ticket))),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// let responses = client.create_many(Some("davinci"), completions, 8).await;
+    /// for (ticket, response) in tickets.iter().zip(responses) {
+    ///     match response {
+    ///         Ok(response) => println!("{}: {}", ticket, response.choices[0].text),
+    ///         Err(error) => eprintln!("{}: {}", ticket, error),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn create_many<T, I>(
+        &self,
+        engine_id: Option<&str>,
+        models: I,
+        concurrency: usize
+    ) -> Vec<Result<T::Output, ResponseError>>
+        where T: HttpEndpoint, I: IntoIterator<Item = T> {
+        self.create_many_with_progress(engine_id, models, concurrency, |_| {}).await
+    }
+
+    /// Returns the responses of many requests of the endpoint like [`Client::create_many`],
+    /// and reports the [`Progress`] of the batch after each finished request.
+    ///
+    /// # Example
+    /// ```
+    /// use hyper::StatusCode;
+    /// use openai_rs::client::Progress;
+    /// use openai_rs::endpoints::edits::Edit;
+    /// use openai_rs::openai;
+    /// use openai_rs::transport::{MockResponse, MockTransport};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let edit = serde_json::json!({ "object": "edit", "created": 0, "choices": [] });
+    /// let transport = MockTransport::new();
+    /// transport.push(MockResponse::json(StatusCode::OK, &edit));
+    /// transport.push(MockResponse::new(StatusCode::BAD_REQUEST, "{}"));
+    /// transport.push(MockResponse::json(StatusCode::OK, &edit));
+    ///
+    /// let client = openai::builder("api_key").transport(transport).build().unwrap();
+    ///
+    /// let mut reported = Vec::new();
+    /// let responses = client.create_many_with_progress(
+    ///     Some("text-davinci-edit-001"),
+    ///     vec![Edit::default(), Edit::default(), Edit::default()],
+    ///     2,
+    ///     |progress| reported.push(progress)
+    /// ).await;
+    ///
+    /// assert_eq!(responses.len(), 3);
+    /// assert_eq!(responses.iter().filter(|response| response.is_err()).count(), 1);
+    /// assert_eq!(reported.last(), Some(&Progress { completed: 3, failed: 1, total: Some(3) }));
+    /// # }
+    /// ```
+    pub async fn create_many_with_progress<T, I, F>(
+        &self,
+        engine_id: Option<&str>,
+        models: I,
+        concurrency: usize,
+        mut on_progress: F
+    ) -> Vec<Result<T::Output, ResponseError>>
+        where T: HttpEndpoint, I: IntoIterator<Item = T>, F: FnMut(Progress) {
+        let models = models.into_iter();
+        let (lower, upper) = models.size_hint();
+        let mut progress = Progress {
+            total: (Some(lower) == upper).then_some(lower),
+            ..Default::default()
+        };
+
+        let mut responses = stream::iter(models.enumerate())
+            .map(|(index, model)| async move { (index, self.create(engine_id, &model).await) })
+            .buffer_unordered(concurrency.max(1));
+
+        let mut results = Vec::with_capacity(lower);
+        while let Some((index, result)) = responses.next().await {
+            progress.completed += 1;
+            if result.is_err() {
+                progress.failed += 1;
+            }
+
+            if results.len() <= index {
+                results.resize_with(index + 1, || None);
+            }
+            results[index] = Some(result);
+            on_progress(progress);
+        }
+
+        results.into_iter()
+            .map(|result| result.expect("every request of the batch finished"))
+            .collect()
+    }
+
+    /// Returns a stream of the incremental responses of the endpoint,
+    /// which are sent by the OpenAI API as server-sent events.
+    /// The endpoint has to request streaming itself, e.g. by setting `stream` of the `Completion`.
+    /// If the API answers without server-sent events, the stream yields the body as single chunk.
+    /// The chunks are deserialized into the `Chunk` of the endpoint, e.g.
+    /// [`ChatCompletionChunk`](crate::endpoints::chat::ChatCompletionChunk) for a `ChatCompletion`.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine_id` - The engine id to use. Due to few endpoints this can be optional.
+    /// * `model` - The model to use. Each struct in the endpoints module is a corresponding endpoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use futures_util::StreamExt;
+    /// use openai_rs::client::Client;
+    /// use openai_rs::endpoints::completion::{Completion, CompletionResponse};
+    /// use openai_rs::openai;
+    /// use openai_rs::stream::EventStream;
+    ///
+    /// # async fn example() {
+    /// let client: Client = openai::new("api_key");
+    ///
+    /// let completion = Completion {
+    ///     prompt: Some(Cow::Borrowed("Say this is a test")),
+    ///     stream: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut stream: EventStream<CompletionResponse> = client.create_stream(
+    ///     Some("davinci"), &completion
+    /// ).await.unwrap();
+    /// while let Some(chunk) = stream.next().await {
+    ///     for choice in chunk.unwrap().choices {
+    ///         print!("{}", choice.text);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn create_stream<T>(
+        &self,
+        engine_id: Option<&str>,
+        model: &T
+    ) -> Result<EventStream<T::Chunk>, ResponseError>
+        where T: Streaming {
+        self.moderate(model).await?;
+        let response = self.retrying_request(model, || self.submit(engine_id, model)).await?;
+
+        if is_event_stream(response.headers()) {
+            return Ok(EventStream::new(response, self.read_timeout)
+                .with_usage(self.usage.clone(), &self.tenant));
+        }
+
+        let body = timeout(self.read_timeout, hyper::body::to_bytes(response.into_body())).await??;
+        let chunk = serde_json::from_slice::<T::Chunk>(&body).map_err(ResponseError::from);
+        if let Some(usage) = chunk.as_ref().ok().and_then(UsageReport::usage) {
+            self.usage.record(&self.tenant, usage);
+        }
+
+        Ok(EventStream::from_single(chunk))
+    }
+
+    /// Returns the body of the response as stream of raw chunks, e.g. the audio of a
+    /// [`Speech`](crate::endpoints::audio::Speech), which can be played or written while it is received.
+    ///
+    /// # Example
+    /// ```
+    /// use std::borrow::Cow;
+    /// use futures_util::StreamExt;
+    /// use openai_rs::client::Client;
+    /// use openai_rs::endpoints::audio::Speech;
+    /// use openai_rs::openai;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// # async fn example() {
+    /// let client: Client = openai::new("api_key");
+    ///
+    /// let speech = Speech {
+    ///     input: Cow::Borrowed("Thank you for calling, how can I help?"),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut audio = client.create_byte_stream(None, &speech).await.unwrap();
+    /// let mut file = tokio::fs::File::create("greeting.mp3").await.unwrap();
+    /// while let Some(chunk) = audio.next().await {
+    ///     file.write_all(&chunk.unwrap()).await.unwrap();
+    /// }
+    /// # }
+    /// ```
+    pub async fn create_byte_stream<T>(
+        &self,
+        engine_id: Option<&str>,
+        model: &T
+    ) -> Result<ByteStream, ResponseError>
+        where T: HttpEndpoint {
+        self.moderate(model).await?;
+        let response = self.retrying_request(model, || self.submit(engine_id, model)).await?;
+
+        Ok(byte_stream(response.into_body(), self.read_timeout))
+    }
+
+    /// Sends the request of the endpoint, retrying it according to the retry policy,
+    /// and returns the body of the successful response.
+    pub(crate) async fn execute<T>(
+        &self,
+        engine_id: Option<&str>,
+        model: &T
+    ) -> Result<Bytes, ResponseError>
+        where T: HttpEndpoint {
+        self.retrying_request(model, || async {
+            let response = self.submit(engine_id, model).await?;
+            timeout(self.read_timeout, hyper::body::to_bytes(response.into_body())).await?
+                .map_err(ResponseError::from)
+        }).await
+    }
+
+    /// Moderates the prompt of the endpoint if the client has a moderation gate,
+    /// and fails with [`ResponseError::Flagged`] if the gate blocks it.
+    async fn moderate<T>(&self, model: &T) -> Result<(), ResponseError>
+        where T: HttpEndpoint {
+        let gate = match &self.moderation {
+            Some(gate) => gate,
+            None => return Ok(())
+        };
+
+        let prompt = model.prompt();
+        if prompt.iter().all(|text| text.trim().is_empty()) {
+            return Ok(());
+        }
+
+        let moderation = Moderation {
+            input: ModerationInput::Texts(prompt),
+            model: gate.model.as_deref().map(Cow::Borrowed),
+        };
+        let body = self.execute(None, &moderation).await?;
+        let response: ModerationResponse = serde_json::from_slice(&body)?;
+
+        let blocked = gate.blocked(&response);
+        if blocked.is_empty() {
+            Ok(())
+        } else {
+            Err(ResponseError::Flagged(blocked))
+        }
+    }
+
+    /// Runs the attempt until it succeeds or the retry policy gives up.
+    pub(crate) async fn retrying<F, Fut, R>(&self, mut attempt_fn: F) -> Result<R, ResponseError>
+        where F: FnMut() -> Fut, Fut: Future<Output = Result<R, ResponseError>> {
+        let mut attempt = 1;
+        loop {
+            match attempt_fn().await {
+                Ok(result) => return Ok(result),
+                Err(error) => match self.retry.delay(attempt, &error) {
+                    Some(delay) => {
+                        debug!("Retrying attempt {} in {:?}: {}", attempt, delay, error);
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    },
+                    None => return Err(error)
                 }
-            },
-            Err(error) => Err(error.into())
+            }
         }
     }
-}
\ No newline at end of file
+
+    /// Runs the attempt of a request of the endpoint like [`Client::retrying`], but only once
+    /// if the request cannot be sent again, so its error is not replaced by that of a failed retry.
+    async fn retrying_request<T, F, Fut, R>(&self, model: &T, mut attempt_fn: F) -> Result<R, ResponseError>
+        where T: HttpEndpoint, F: FnMut() -> Fut, Fut: Future<Output = Result<R, ResponseError>> {
+        if model.replayable() {
+            self.retrying(attempt_fn).await
+        } else {
+            attempt_fn().await
+        }
+    }
+
+    /// Sends a single request of the endpoint once the rate limiter admits it.
+    async fn submit<T>(
+        &self,
+        engine_id: Option<&str>,
+        model: &T
+    ) -> Result<HyperResponse<Body>, ResponseError>
+        where T: HttpEndpoint {
+        let request = model.request(&self.api_url(), &self.api_key, engine_id)?;
+        let rate_limiter = match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter,
+            None => return self.send(request).await
+        };
+
+        let tokens = rate_limiter.estimate(&model.input_text(), model.max_tokens()) + model.input_tokens();
+        rate_limiter.acquire(tokens).await;
+        let response = self.send(request).await;
+        match &response {
+            Ok(response) => rate_limiter.update(&RateLimit::from_headers(response.headers())),
+            Err(ResponseError::Api(error)) => rate_limiter.update(&error.rate_limit),
+            Err(_) => {}
+        }
+
+        response
+    }
+
+    /// Sends a single request with the default headers of the client
+    /// and returns the successful response, whose body has not been read yet.
+    async fn send(&self, mut request: Request<Body>) -> Result<HyperResponse<Body>, ResponseError> {
+        for (name, value) in &self.headers {
+            if !request.headers().contains_key(name) {
+                request.headers_mut().insert(name, value.clone());
+            }
+        }
+
+        self.dispatch(request).await
+    }
+
+    /// Sends a single request as it is, e.g. to a host other than the API,
+    /// and returns the successful response, whose body has not been read yet.
+    pub(crate) async fn dispatch(&self, request: Request<Body>) -> Result<HyperResponse<Body>, ResponseError> {
+        let response = timeout(self.request_timeout, self.transport.send(request)).await??;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let (parts, body) = response.into_parts();
+        let body = timeout(self.read_timeout, hyper::body::to_bytes(body)).await??;
+        let error = ApiError::new(parts.status, &parts.headers, &body);
+        Err(ResponseError::Api(Box::new(error)))
+    }
+}
+
+/// Awaits the future, failing with [`ResponseError::Timeout`] if it does not finish in time.
+pub(crate) async fn timeout<F: Future>(
+    duration: Option<Duration>,
+    future: F
+) -> Result<F::Output, ResponseError> {
+    match duration {
+        Some(duration) => tokio::time::timeout(duration, future).await
+            .map_err(|_| ResponseError::Timeout(duration)),
+        None => Ok(future.await)
+    }
+}
+
+/// Normalizes the api root to either be empty or start with a slash without a trailing one.
+pub(crate) fn normalize_api_root(api_root: &str) -> String {
+    let trimmed = api_root.trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
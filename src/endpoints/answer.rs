@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
-use hyper::{Body, Request};
-use serde::Serialize;
-use crate::endpoints::Model;
+use serde::{Deserialize, Serialize};
+use crate::cache::Caching;
+use crate::endpoints::{SelectedDocument, Usage, UsageReport};
+use crate::endpoints::models::ModelId;
 use crate::endpoints::request::Endpoint;
 
 /// Given a question, a set of documents, and some examples, the API generates an answer to the
@@ -12,7 +13,7 @@ use crate::endpoints::request::Endpoint;
 #[derive(Debug, Clone, Serialize)]
 pub struct Answer<'a> {
     /// ID of the engine to use for completion. You can select one of ada, babbage, curie, or davinci.
-    pub model: Model,
+    pub model: ModelId,
 
     /// The question to answer.
     pub question: Cow<'a, str>,
@@ -35,7 +36,7 @@ pub struct Answer<'a> {
     pub file: Option<Cow<'a, str>>,
 
     /// ID of the engine to use for Search. You can select one of ada, babbage, curie, or davinci.
-    pub search_model: Model,
+    pub search_model: ModelId,
 
     /// The maximum number of documents to be ranked by Search when using file.
     /// Setting it to a higher value leads to improved accuracy but with increased latency and cost.
@@ -93,13 +94,13 @@ pub struct Answer<'a> {
 impl Default for Answer<'_> {
     fn default() -> Self {
         Self {
-            model: Model::Ada,
+            model: ModelId::ADA,
             question: Cow::Borrowed(""),
             examples: Vec::new(),
             examples_context: Cow::Borrowed(""),
             documents: Vec::new(),
             file: None,
-            search_model: Model::Ada,
+            search_model: ModelId::ADA,
             max_rerank: 200,
             temperature: 0.0,
             logprobs: 0,
@@ -116,15 +117,41 @@ impl Default for Answer<'_> {
 }
 
 impl Endpoint for Answer<'_> {
-    const ENDPOINT: &'static str = "https://api.openai.com/v1/answers";
+    const ENDPOINT: &'static str = "/answers";
 
-    fn request(&self, auth_token: &str, _engine_id: Option<&str>) -> Request<Body> {
-        let serialized = serde_json::to_string(&self)
-            .expect("Failed to serialize Answer");
-        let endpoint = Self::ENDPOINT.to_owned();
-        trace!("endpoint={}, serialized={}", endpoint, serialized);
+    type Output = AnswerResponse;
 
+    fn input_text(&self) -> Vec<Cow<'_, str>> {
+        [&self.question, &self.examples_context].into_iter()
+            .chain(self.examples.iter().flatten())
+            .chain(&self.documents)
+            .map(|text| Cow::Borrowed(&**text))
+            .collect()
+    }
+
+    fn max_tokens(&self) -> Option<u32> {
+        Some(self.max_tokens.saturating_mul(self.n.max(1)))
+    }
 
-        super::request::post!(endpoint, auth_token, serialized)
+    fn caching(&self) -> Caching {
+        Caching::deterministic_if(self.temperature == 0.)
     }
-}
\ No newline at end of file
+}
+
+/// The response of an [`Answer`] with the generated answers.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AnswerResponse {
+    pub object: String,
+    pub model: String,
+    pub search_model: ModelId,
+    pub completion: String,
+    pub answers: Vec<String>,
+    pub selected_documents: Vec<SelectedDocument>,
+    pub usage: Option<Usage>,
+}
+
+impl UsageReport for AnswerResponse {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
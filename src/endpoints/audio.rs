@@ -0,0 +1,462 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use hyper::body::Bytes;
+use serde::{Deserialize, Serialize};
+use crate::endpoints::{ResponseError, Usage, UsageReport};
+use crate::endpoints::multipart::{FileSource, Multipart};
+use crate::endpoints::request::{json, HttpEndpoint, RequestBody};
+
+/// Transcribes audio into the input language.
+///
+/// # Example
+/// ```
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::audio::{AudioResponseFormat, TimestampGranularity, Transcription};
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let transcription = Transcription {
+///     response_format: Some(AudioResponseFormat::VerboseJson),
+///     timestamp_granularities: vec![TimestampGranularity::Segment, TimestampGranularity::Word],
+///     ..Transcription::from_path("support-call.mp3")
+/// };
+///
+/// let transcript = client.create(None, &transcription).await.unwrap();
+/// for segment in transcript.segments {
+///     println!("[{:.1}s - {:.1}s] {}", segment.start, segment.end, segment.text);
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Transcription<'a> {
+    /// The audio file to transcribe, in one of these formats:
+    /// flac, mp3, mp4, mpeg, mpga, m4a, ogg, wav, or webm.
+    pub file: AudioFile<'a>,
+
+    /// ID of the model to use, e.g. `whisper-1`.
+    pub model: Cow<'a, str>,
+
+    /// The language of the input audio in ISO-639-1 format, e.g. `en`.
+    pub language: Option<Cow<'a, str>>,
+
+    /// An optional text to guide the model's style or continue a previous audio segment.
+    /// The prompt should match the audio language.
+    pub prompt: Option<Cow<'a, str>>,
+
+    /// The format of the transcript, `json` by default.
+    pub response_format: Option<AudioResponseFormat>,
+
+    /// The sampling temperature, between 0 and 1.
+    pub temperature: Option<f32>,
+
+    /// The timestamp granularities to populate, which requires the `verbose_json` response format.
+    pub timestamp_granularities: Vec<TimestampGranularity>,
+}
+
+impl<'a> Transcription<'a> {
+    /// Returns a transcription of the audio file at the path with `whisper-1`.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            file: AudioFile::from_path(path),
+            model: Cow::Borrowed(DEFAULT_MODEL),
+            language: None,
+            prompt: None,
+            response_format: None,
+            temperature: None,
+            timestamp_granularities: Vec::new()
+        }
+    }
+}
+
+impl HttpEndpoint for Transcription<'_> {
+    type Output = Transcript;
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/audio/transcriptions".to_owned())
+    }
+
+    fn body(&self) -> Result<RequestBody, ResponseError> {
+        let mut form = Multipart::new()
+            .file("file", &self.file.filename, &self.file.file)?
+            .text("model", &self.model)
+            .optional("language", self.language.as_ref())
+            .optional("prompt", self.prompt.as_ref())
+            .optional("response_format", self.response_format)
+            .optional("temperature", self.temperature);
+        for granularity in &self.timestamp_granularities {
+            form = form.text("timestamp_granularities[]", granularity);
+        }
+
+        Ok(RequestBody::Multipart(form))
+    }
+
+    fn replayable(&self) -> bool {
+        self.file.file.is_replayable()
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        Transcript::parse(body)
+    }
+}
+
+/// Translates audio into English.
+#[derive(Debug)]
+pub struct Translation<'a> {
+    /// The audio file to translate, in one of these formats:
+    /// flac, mp3, mp4, mpeg, mpga, m4a, ogg, wav, or webm.
+    pub file: AudioFile<'a>,
+
+    /// ID of the model to use, e.g. `whisper-1`.
+    pub model: Cow<'a, str>,
+
+    /// An optional text in English to guide the model's style or continue a previous audio segment.
+    pub prompt: Option<Cow<'a, str>>,
+
+    /// The format of the transcript, `json` by default.
+    pub response_format: Option<AudioResponseFormat>,
+
+    /// The sampling temperature, between 0 and 1.
+    pub temperature: Option<f32>,
+}
+
+impl<'a> Translation<'a> {
+    /// Returns a translation of the audio file at the path with `whisper-1`.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            file: AudioFile::from_path(path),
+            model: Cow::Borrowed(DEFAULT_MODEL),
+            prompt: None,
+            response_format: None,
+            temperature: None
+        }
+    }
+}
+
+impl HttpEndpoint for Translation<'_> {
+    type Output = Transcript;
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/audio/translations".to_owned())
+    }
+
+    fn body(&self) -> Result<RequestBody, ResponseError> {
+        let form = Multipart::new()
+            .file("file", &self.file.filename, &self.file.file)?
+            .text("model", &self.model)
+            .optional("prompt", self.prompt.as_ref())
+            .optional("response_format", self.response_format)
+            .optional("temperature", self.temperature);
+
+        Ok(RequestBody::Multipart(form))
+    }
+
+    fn replayable(&self) -> bool {
+        self.file.file.is_replayable()
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        Transcript::parse(body)
+    }
+}
+
+/// The model which transcribes and translates audio if none is set.
+const DEFAULT_MODEL: &str = "whisper-1";
+
+/// An audio file which is uploaded to transcribe or translate it.
+#[derive(Debug)]
+pub struct AudioFile<'a> {
+    pub file: FileSource,
+
+    /// The name of the file, whose extension tells the API the format of the audio, e.g. `call.mp3`.
+    pub filename: Cow<'a, str>,
+}
+
+impl<'a> AudioFile<'a> {
+    /// Returns the audio file at the path, named after the last component of the path.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let filename = path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self {
+            file: FileSource::Path(path),
+            filename: Cow::Owned(filename),
+        }
+    }
+
+    /// Returns the audio in memory.
+    pub fn from_bytes(filename: impl Into<Cow<'a, str>>, content: impl Into<Bytes>) -> Self {
+        Self {
+            file: FileSource::Bytes(content.into()),
+            filename: filename.into(),
+        }
+    }
+}
+
+/// The format of a transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioResponseFormat {
+    Json,
+    Text,
+    Srt,
+    VerboseJson,
+    Vtt,
+}
+
+impl Display for AudioResponseFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Json => "json",
+            Self::Text => "text",
+            Self::Srt => "srt",
+            Self::VerboseJson => "verbose_json",
+            Self::Vtt => "vtt",
+        })
+    }
+}
+
+/// The granularity of the timestamps of a `verbose_json` transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampGranularity {
+    Word,
+    Segment,
+}
+
+impl Display for TimestampGranularity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Word => "word",
+            Self::Segment => "segment",
+        })
+    }
+}
+
+/// The transcript of a [`Transcription`] or [`Translation`] in any response format.
+///
+/// `json` only returns the text and `verbose_json` additionally the language, duration,
+/// and the segments and words with their timestamps. `srt` and `vtt` subtitles are kept as text
+/// and their cues are parsed into segments, `text` is returned as it is.
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::audio::Transcript;
+///
+/// let transcript = Transcript::parse("1\n00:00:00,000 --> 00:00:02,500\nHello, how can I help?\n\n\
+///     2\n00:00:02,500 --> 00:00:04,120\nMy order did not arrive.\n".into()).unwrap();
+///
+/// assert_eq!(transcript.segments.len(), 2);
+/// assert_eq!(transcript.segments[1].start, 2.5);
+/// assert_eq!(transcript.segments[1].end, 4.12);
+/// assert_eq!(transcript.segments[1].text, "My order did not arrive.");
+///
+/// let transcript = Transcript::parse(r#"{
+///     "task": "transcribe",
+///     "language": "english",
+///     "duration": 4.12,
+///     "text": "Hello, how can I help?",
+///     "words": [{ "word": "Hello", "start": 0.0, "end": 0.48 }],
+///     "segments": [{ "id": 0, "seek": 0, "start": 0.0, "end": 2.5, "text": " Hello, how can I help?" }]
+/// }"#.into()).unwrap();
+///
+/// assert_eq!(transcript.language.as_deref(), Some("english"));
+/// assert_eq!(transcript.words[0].end, 0.48);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Transcript {
+    /// The transcribed text, or the subtitles of the `srt` and `vtt` formats.
+    pub text: String,
+
+    /// The task which produced the transcript, `transcribe` or `translate`.
+    #[serde(default)]
+    pub task: Option<String>,
+
+    /// The language of the input audio.
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// The duration of the input audio in seconds.
+    #[serde(default)]
+    pub duration: Option<f64>,
+
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+
+    #[serde(default)]
+    pub words: Vec<TranscriptWord>,
+
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+impl Transcript {
+    /// Parses a transcript in any response format.
+    pub fn parse(body: Bytes) -> Result<Self, ResponseError> {
+        if body.trim_ascii_start().starts_with(b"{") {
+            return json(body);
+        }
+
+        let text = String::from_utf8_lossy(&body).into_owned();
+        let segments = parse_cues(&text);
+        Ok(Self { text, segments, ..Default::default() })
+    }
+}
+
+impl UsageReport for Transcript {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
+
+/// A segment of a transcript with its timestamps in seconds.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TranscriptSegment {
+    pub id: u32,
+
+    /// The seek offset of the segment.
+    pub seek: u64,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+
+    /// The token ids of the text.
+    pub tokens: Vec<u32>,
+    pub temperature: f64,
+
+    /// The average log probability of the segment. Below -1, the logprobs might have failed.
+    pub avg_logprob: f64,
+
+    /// The compression ratio of the segment. Above 2.4, the compression might have failed.
+    pub compression_ratio: f64,
+
+    /// The probability of no speech in the segment.
+    pub no_speech_prob: f64,
+}
+
+/// A word of a transcript with its timestamps in seconds.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct TranscriptWord {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Parses the cues of `srt` or `vtt` subtitles into segments. Returns no segments for plain text.
+fn parse_cues(subtitles: &str) -> Vec<TranscriptSegment> {
+    let subtitles = subtitles.replace("\r\n", "\n");
+    let mut segments = Vec::new();
+
+    for block in subtitles.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let timing = match lines.next() {
+            Some(timing) => timing,
+            None => continue
+        };
+
+        let (start, end) = match timing.split_once("-->") {
+            Some((start, end)) => (start.trim(), end.split_whitespace().next().unwrap_or_default()),
+            None => continue
+        };
+        if let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) {
+            segments.push(TranscriptSegment {
+                id: segments.len() as u32,
+                start,
+                end,
+                text: lines.collect::<Vec<_>>().join("\n"),
+                ..Default::default()
+            });
+        }
+    }
+
+    segments
+}
+
+/// Parses a timestamp of subtitles, e.g. `00:01:02,500` or `01:02.500`, into seconds.
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    timestamp.replace(',', ".")
+        .split(':')
+        .try_fold(0., |seconds, part| part.parse::<f64>().ok().map(|part| seconds * 60. + part))
+}
+
+/// Generates audio from the input text. The audio is returned as raw bytes by
+/// [`Client::create`](crate::client::Client::create) or streamed by
+/// [`Client::create_byte_stream`](crate::client::Client::create_byte_stream).
+#[derive(Debug, Clone, Serialize)]
+pub struct Speech<'a> {
+    /// One of the text-to-speech models, e.g. `tts-1`, `tts-1-hd` or `gpt-4o-mini-tts`.
+    pub model: Cow<'a, str>,
+
+    /// The text to generate audio for, at most 4096 characters.
+    pub input: Cow<'a, str>,
+
+    /// The voice to use, e.g. `alloy`, `echo`, `fable`, `onyx`, `nova` or `shimmer`.
+    pub voice: Cow<'a, str>,
+
+    /// Control the voice of the generated audio with additional instructions.
+    /// Does not work with `tts-1` or `tts-1-hd`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<Cow<'a, str>>,
+
+    /// The format of the audio, `mp3` by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<SpeechFormat>,
+
+    /// The speed of the generated audio, between 0.25 and 4.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+}
+
+impl Default for Speech<'_> {
+    fn default() -> Self {
+        Self {
+            model: Cow::Borrowed("tts-1"),
+            input: Cow::Borrowed(""),
+            voice: Cow::Borrowed("alloy"),
+            instructions: None,
+            response_format: None,
+            speed: None
+        }
+    }
+}
+
+impl HttpEndpoint for Speech<'_> {
+    type Output = Bytes;
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/audio/speech".to_owned())
+    }
+
+    fn body(&self) -> Result<RequestBody, ResponseError> {
+        serde_json::to_string(self)
+            .map(RequestBody::Json)
+            .map_err(ResponseError::RequestSerialization)
+    }
+
+    fn prompt(&self) -> Vec<Cow<'_, str>> {
+        vec![Cow::Borrowed(&*self.input)]
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        Ok(body)
+    }
+}
+
+/// The format of synthesized speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeechFormat {
+    Mp3,
+    Opus,
+    Aac,
+    Flac,
+    Wav,
+
+    /// Raw samples in 24kHz (16-bit signed, low-endian), without the header.
+    Pcm,
+}
@@ -0,0 +1,593 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::Duration;
+use hyper::body::Bytes;
+use hyper::{HeaderMap, Method, StatusCode};
+use hyper::header::HeaderValue;
+use serde::{Deserialize, Serialize};
+use crate::client::Client;
+use crate::endpoints::{ApiError, ResponseError, UsageReport};
+use crate::endpoints::files::{DownloadFile, UploadFile};
+use crate::endpoints::request::{encode_path_segment, json, Endpoint, HttpEndpoint};
+
+/// The time frame within which a batch is processed.
+pub const COMPLETION_WINDOW: &str = "24h";
+
+/// The requests of a batch as JSONL input file, one request of the endpoint `T` per line.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use std::time::Duration;
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::batch::BatchFile;
+/// use openai_rs::endpoints::chat::{ChatCompletion, ChatMessage};
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let tickets = [("ticket-1", "My order did not arrive."), ("ticket-2", "How do I reset my password?")];
+/// let mut file = BatchFile::new(None);
+/// for (id, ticket) in tickets {
+///     file.push(id, &ChatCompletion {
+///         model: Cow::Borrowed("gpt-4o-mini"),
+///         messages: vec![ChatMessage::user(format!("Summarize the ticket: {}", ticket))],
+///         ..Default::default()
+///     }).unwrap();
+/// }
+///
+/// let batch = client.submit_batch(&file).await.unwrap();
+/// let batch = client.wait_for_batch(&batch.id, Duration::from_secs(60)).await.unwrap();
+///
+/// let results = client.batch_results::<ChatCompletion>(&batch).await.unwrap();
+/// for (id, _) in tickets {
+///     match &results[id] {
+///         Ok(response) => println!("{}: {:?}", id, response.choices[0].message),
+///         Err(error) => eprintln!("{}: {}", id, error),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchFile<T> {
+    engine_id: Option<String>,
+    lines: Vec<BatchLine>,
+    endpoint: PhantomData<fn(&T)>,
+}
+
+/// A request of a [`BatchFile`], whose url is joined with the api root once the file is written.
+#[derive(Debug, Clone)]
+struct BatchLine {
+    custom_id: String,
+    path: String,
+    body: serde_json::Value,
+}
+
+impl<T: Endpoint> BatchFile<T> {
+    /// Returns an empty file for requests of the endpoint, which are scoped to the engine if given.
+    pub fn new(engine_id: Option<&str>) -> Self {
+        Self {
+            engine_id: engine_id.map(str::to_owned),
+            lines: Vec::new(),
+            endpoint: PhantomData,
+        }
+    }
+
+    /// Adds the request to the file. The `custom_id` identifies its result
+    /// and must be unique within the batch.
+    ///
+    /// # Example
+    /// ```
+    /// use std::borrow::Cow;
+    /// use openai_rs::endpoints::batch::BatchFile;
+    /// use openai_rs::endpoints::embeddings::{Embedding, EmbeddingInput};
+    /// use openai_rs::openai::DEFAULT_API_ROOT;
+    ///
+    /// let mut file = BatchFile::new(None);
+    /// file.push("doc-1", &Embedding {
+    ///     model: Cow::Borrowed("text-embedding-3-small"),
+    ///     input: EmbeddingInput::Text(Cow::Borrowed("The food was delicious.")),
+    ///     ..Default::default()
+    /// }).unwrap();
+    ///
+    /// let line: serde_json::Value = serde_json::from_slice(&file.to_jsonl(DEFAULT_API_ROOT)).unwrap();
+    /// assert_eq!(line["custom_id"], "doc-1");
+    /// assert_eq!(line["method"], "POST");
+    /// assert_eq!(line["url"], "/v1/embeddings");
+    /// assert_eq!(line["body"]["input"], "The food was delicious.");
+    /// ```
+    pub fn push(&mut self, custom_id: &str, model: &T) -> Result<&mut Self, ResponseError> {
+        self.lines.push(BatchLine {
+            custom_id: custom_id.to_owned(),
+            path: Endpoint::path(model, self.engine_id.as_deref())?,
+            body: serde_json::to_value(model).map_err(ResponseError::RequestSerialization)?,
+        });
+        Ok(self)
+    }
+
+    /// Returns the number of requests in the file.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns whether the file has no requests, which cannot be submitted as a batch.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns the path of the endpoint relative to the api root, e.g. `/chat/completions`,
+    /// or `None` if the file is empty.
+    pub fn path(&self) -> Option<&str> {
+        self.lines.last().map(|line| line.path.as_str())
+    }
+
+    /// Returns the content of the file, where the url of each request starts with the api root,
+    /// e.g. [`DEFAULT_API_ROOT`](crate::openai::DEFAULT_API_ROOT) or [`Client::api_root`].
+    pub fn to_jsonl(&self, api_root: &str) -> Bytes {
+        #[derive(Serialize)]
+        struct Line<'a> {
+            custom_id: &'a str,
+            method: &'static str,
+            url: String,
+            body: &'a serde_json::Value,
+        }
+
+        let mut content = Vec::new();
+        for line in &self.lines {
+            let url = format!("{}{}", api_root, line.path);
+            let line = Line { custom_id: &line.custom_id, method: "POST", url, body: &line.body };
+            serde_json::to_writer(&mut content, &line).expect("a JSON value is always serializable");
+            content.push(b'\n');
+        }
+
+        Bytes::from(content)
+    }
+
+    /// Returns the upload of the file with the purpose `batch`, see [`BatchFile::to_jsonl`].
+    pub fn upload(&self, filename: &str, api_root: &str) -> UploadFile<'static> {
+        UploadFile::from_bytes(filename.to_owned(), self.to_jsonl(api_root), "batch")
+    }
+}
+
+/// Creates and executes a batch from an uploaded file of requests.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateBatch<'a> {
+    /// The ID of an uploaded file with the purpose `batch` that contains the requests.
+    pub input_file_id: Cow<'a, str>,
+
+    /// The endpoint of all requests of the batch, e.g. `/v1/chat/completions`.
+    pub endpoint: Cow<'a, str>,
+
+    /// The time frame within which the batch should be processed, only `24h` is supported.
+    pub completion_window: Cow<'a, str>,
+
+    /// Up to 16 key-value pairs which are attached to the batch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl Default for CreateBatch<'_> {
+    fn default() -> Self {
+        Self {
+            input_file_id: Cow::Borrowed(""),
+            endpoint: Cow::Borrowed(""),
+            completion_window: Cow::Borrowed(COMPLETION_WINDOW),
+            metadata: None
+        }
+    }
+}
+
+impl Endpoint for CreateBatch<'_> {
+    const ENDPOINT: &'static str = "/batches";
+
+    type Output = Batch;
+}
+
+/// Returns a batch.
+#[derive(Debug, Default, Clone)]
+pub struct RetrieveBatch<'a> {
+    /// The ID of the batch.
+    pub batch_id: Cow<'a, str>,
+}
+
+impl HttpEndpoint for RetrieveBatch<'_> {
+    type Output = Batch;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/batches/{}", encode_path_segment(&self.batch_id)))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Cancels an in-progress batch. The batch is `cancelling` for up to 10 minutes
+/// before it is `cancelled`, and the results of finished requests are kept in the output file.
+#[derive(Debug, Default, Clone)]
+pub struct CancelBatch<'a> {
+    /// The ID of the batch.
+    pub batch_id: Cow<'a, str>,
+}
+
+impl HttpEndpoint for CancelBatch<'_> {
+    type Output = Batch;
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/batches/{}/cancel", encode_path_segment(&self.batch_id)))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Lists the batches of the organization, most recent first.
+#[derive(Debug, Default, Clone)]
+pub struct ListBatches<'a> {
+    /// The ID of the last batch of the previous page.
+    pub after: Option<Cow<'a, str>>,
+
+    /// The number of batches to retrieve, 20 by default.
+    pub limit: Option<u32>,
+}
+
+impl HttpEndpoint for ListBatches<'_> {
+    type Output = BatchList;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/batches".to_owned())
+    }
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(after) = &self.after {
+            query.push(("after", after.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+
+        query
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// A batch of requests.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Batch {
+    pub id: String,
+    pub object: String,
+
+    /// The endpoint of the requests of the batch.
+    pub endpoint: String,
+
+    /// The errors of the validation of the input file.
+    #[serde(default)]
+    pub errors: Option<BatchErrors>,
+    pub input_file_id: String,
+    pub completion_window: String,
+    pub status: BatchStatus,
+
+    /// The ID of the file with the results of the successful requests.
+    #[serde(default)]
+    pub output_file_id: Option<String>,
+
+    /// The ID of the file with the results of the failed requests.
+    #[serde(default)]
+    pub error_file_id: Option<String>,
+
+    /// The Unix timestamp (in seconds) of the creation of the batch.
+    pub created_at: u64,
+    #[serde(default)]
+    pub in_progress_at: Option<u64>,
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub finalizing_at: Option<u64>,
+    #[serde(default)]
+    pub completed_at: Option<u64>,
+    #[serde(default)]
+    pub failed_at: Option<u64>,
+    #[serde(default)]
+    pub expired_at: Option<u64>,
+    #[serde(default)]
+    pub cancelling_at: Option<u64>,
+    #[serde(default)]
+    pub cancelled_at: Option<u64>,
+
+    #[serde(default)]
+    pub request_counts: Option<RequestCounts>,
+
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl UsageReport for Batch {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Validating,
+    Failed,
+    InProgress,
+    Finalizing,
+    Completed,
+    Expired,
+    Cancelling,
+    Cancelled,
+
+    /// A status which is not known to this crate.
+    #[serde(other)]
+    Unknown,
+}
+
+impl BatchStatus {
+    /// Returns whether the batch ended and its status will not change anymore.
+    /// An expired or cancelled batch may still have results of the requests which finished in time.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Failed | Self::Completed | Self::Expired | Self::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BatchErrors {
+    pub data: Vec<BatchValidationError>,
+}
+
+/// An error of the validation of the input file of a batch.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BatchValidationError {
+    pub code: Option<String>,
+    pub message: String,
+    pub param: Option<String>,
+
+    /// The line of the input file which caused the error.
+    pub line: Option<u64>,
+}
+
+/// The number of requests of a batch per state.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RequestCounts {
+    pub total: u64,
+    pub completed: u64,
+    pub failed: u64,
+}
+
+/// The response of [`ListBatches`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BatchList {
+    pub object: String,
+    pub data: Vec<Batch>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl UsageReport for BatchList {}
+
+/// The reason why a request of a batch failed without a response, e.g. because the batch expired.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BatchRequestError {
+    pub code: Option<String>,
+    pub message: String,
+}
+
+/// A line of the output or error file of a batch.
+#[derive(Debug, Deserialize)]
+struct ResultLine {
+    custom_id: String,
+    #[serde(default)]
+    response: Option<ResultResponse>,
+    #[serde(default)]
+    error: Option<BatchRequestError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResultResponse {
+    status_code: u16,
+    #[serde(default)]
+    request_id: Option<String>,
+    body: serde_json::Value,
+}
+
+/// Parses the output or error file of a batch into the response of each request by its `custom_id`.
+/// A request which was answered with an error status fails with [`ResponseError::Api`], a request
+/// which was not answered at all with [`ResponseError::BatchRequestFailed`].
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::batch::parse_results;
+/// use openai_rs::endpoints::embeddings::Embedding;
+/// use openai_rs::endpoints::ResponseError;
+///
+/// let output = br#"{"id": "batch_req_1", "custom_id": "doc-1", "response": {"status_code": 200, "request_id": "req_1", "body": {"object": "list", "model": "text-embedding-3-small", "data": [{"object": "embedding", "index": 0, "embedding": [0.1, 0.2]}], "usage": {"prompt_tokens": 5, "total_tokens": 5}}}, "error": null}
+/// {"id": "batch_req_2", "custom_id": "doc-2", "response": {"status_code": 400, "request_id": "req_2", "body": {"error": {"message": "Invalid input", "type": "invalid_request_error"}}}, "error": null}
+/// {"id": "batch_req_3", "custom_id": "doc-3", "response": null, "error": {"code": "batch_expired", "message": "This request could not be executed before the completion window expired."}}
+/// "#;
+///
+/// let results = parse_results::<Embedding>(output).unwrap();
+/// assert_eq!(results["doc-1"].as_ref().unwrap().data[0].embedding, [0.1, 0.2]);
+/// assert!(matches!(&results["doc-2"], Err(ResponseError::Api(error)) if error.status == 400));
+/// assert!(matches!(&results["doc-3"], Err(ResponseError::BatchRequestFailed(error)) if error.code.as_deref() == Some("batch_expired")));
+/// ```
+pub fn parse_results<T: Endpoint>(
+    jsonl: &[u8]
+) -> Result<HashMap<String, Result<T::Output, ResponseError>>, ResponseError> {
+    let mut results = HashMap::new();
+    for line in jsonl.split(|byte| *byte == b'\n') {
+        if line.trim_ascii().is_empty() {
+            continue;
+        }
+
+        let line: ResultLine = serde_json::from_slice(line)?;
+        let result = match (line.response, line.error) {
+            (Some(response), _) => {
+                let body = Bytes::from(serde_json::to_vec(&response.body)?);
+                match StatusCode::from_u16(response.status_code) {
+                    Ok(status) if status.is_success() => json(body),
+                    status => {
+                        let mut headers = HeaderMap::new();
+                        if let Some(request_id) = response.request_id.and_then(|id| HeaderValue::from_str(&id).ok()) {
+                            headers.insert("x-request-id", request_id);
+                        }
+                        let status = status.unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                        Err(ResponseError::Api(Box::new(ApiError::new(status, &headers, &body))))
+                    }
+                }
+            },
+            (None, Some(error)) => Err(ResponseError::BatchRequestFailed(Box::new(error))),
+            (None, None) => Err(ResponseError::BatchRequestFailed(Box::new(BatchRequestError {
+                code: None,
+                message: "The request has neither a response nor an error".to_owned(),
+            }))),
+        };
+
+        results.insert(line.custom_id, result);
+    }
+
+    Ok(results)
+}
+
+impl Client {
+    /// Uploads the file and creates a batch of its requests. The urls of the requests start with
+    /// the api root of the client. Fails with [`ResponseError::EmptyBatch`] before uploading
+    /// anything if the file is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use std::borrow::Cow;
+    /// use hyper::StatusCode;
+    /// use openai_rs::endpoints::ResponseError;
+    /// use openai_rs::endpoints::batch::BatchFile;
+    /// use openai_rs::endpoints::embeddings::{Embedding, EmbeddingInput};
+    /// use openai_rs::openai;
+    /// use openai_rs::transport::{MockResponse, MockTransport};
+    /// use serde_json::json;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let transport = MockTransport::new();
+    /// transport
+    ///     .push(MockResponse::json(StatusCode::OK, &json!({
+    ///         "id": "file-abc123", "object": "file", "bytes": 160, "created_at": 0,
+    ///         "filename": "batch.jsonl", "purpose": "batch"
+    ///     })))
+    ///     .push(MockResponse::json(StatusCode::OK, &json!({
+    ///         "object": "batch", "id": "batch_abc123", "endpoint": "/openai/v1/embeddings",
+    ///         "input_file_id": "file-abc123", "completion_window": "24h", "status": "validating",
+    ///         "created_at": 0
+    ///     })));
+    /// let client = openai::builder("api_key")
+    ///     .api_root("/openai/v1")
+    ///     .transport(transport.clone())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut file = BatchFile::new(None);
+    /// let error = client.submit_batch(&file).await.unwrap_err();
+    /// assert!(matches!(error, ResponseError::EmptyBatch));
+    /// assert!(transport.requests().is_empty());
+    ///
+    /// file.push("doc-1", &Embedding {
+    ///     model: Cow::Borrowed("text-embedding-3-small"),
+    ///     input: EmbeddingInput::Text(Cow::Borrowed("The food was delicious.")),
+    ///     ..Default::default()
+    /// }).unwrap();
+    /// client.submit_batch(&file).await.unwrap();
+    ///
+    /// let requests = transport.requests();
+    /// assert!(String::from_utf8_lossy(&requests[0].body).contains(r#""url":"/openai/v1/embeddings""#));
+    /// let batch: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+    /// assert_eq!(batch["endpoint"], "/openai/v1/embeddings");
+    /// # }
+    /// ```
+    pub async fn submit_batch<T: Endpoint>(&self, file: &BatchFile<T>) -> Result<Batch, ResponseError> {
+        let path = file.path().ok_or(ResponseError::EmptyBatch)?;
+        let uploaded = self.create(None, &file.upload("batch.jsonl", &self.api_root)).await?;
+
+        self.create(None, &CreateBatch {
+            input_file_id: Cow::Owned(uploaded.id),
+            endpoint: Cow::Owned(format!("{}{}", self.api_root, path)),
+            ..Default::default()
+        }).await
+    }
+
+    /// Polls the batch every `poll_interval` until it reached a terminal state and returns it.
+    /// The batch is returned whether it completed, failed, expired or was cancelled,
+    /// as the latter may still have results.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use hyper::StatusCode;
+    /// use openai_rs::endpoints::batch::BatchStatus;
+    /// use openai_rs::openai;
+    /// use openai_rs::transport::{MockResponse, MockTransport};
+    /// use serde_json::json;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let batch = |status: &str| MockResponse::json(StatusCode::OK, &json!({
+    ///     "object": "batch", "id": "batch_abc123", "endpoint": "/v1/chat/completions",
+    ///     "input_file_id": "file-abc123", "completion_window": "24h", "status": status,
+    ///     "created_at": 0, "output_file_id": "file-def456",
+    ///     "request_counts": { "total": 2, "completed": 2, "failed": 0 }
+    /// }));
+    ///
+    /// let transport = MockTransport::new();
+    /// transport.push(batch("in_progress")).push(batch("finalizing")).push(batch("completed"));
+    /// let client = openai::builder("api_key").transport(transport.clone()).build().unwrap();
+    ///
+    /// let batch = client.wait_for_batch("batch_abc123", Duration::from_millis(1)).await.unwrap();
+    /// assert_eq!(batch.status, BatchStatus::Completed);
+    /// assert_eq!(transport.requests().len(), 3);
+    /// # }
+    /// ```
+    pub async fn wait_for_batch(&self, batch_id: &str, poll_interval: Duration) -> Result<Batch, ResponseError> {
+        loop {
+            let batch = self.create(None, &RetrieveBatch { batch_id: Cow::Borrowed(batch_id) }).await?;
+            if batch.status.is_terminal() {
+                return Ok(batch);
+            }
+
+            if let Some(counts) = &batch.request_counts {
+                debug!("{} [{:?}]: {}/{} requests, {} failed",
+                    batch_id, batch.status, counts.completed, counts.total, counts.failed);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Downloads the output and the error file of the batch and returns the response of each request
+    /// by its `custom_id`, see [`parse_results`]. Requests without a result, e.g. of a failed batch,
+    /// are missing. The usage of the responses is recorded like the usage of any other request.
+    pub async fn batch_results<T: Endpoint>(
+        &self,
+        batch: &Batch
+    ) -> Result<HashMap<String, Result<T::Output, ResponseError>>, ResponseError> {
+        let mut results = HashMap::new();
+        for file_id in batch.output_file_id.iter().chain(&batch.error_file_id) {
+            let content = self.create(None, &DownloadFile { file_id: Cow::Borrowed(file_id) }).await?;
+            results.extend(parse_results::<T>(&content)?);
+        }
+
+        for usage in results.values().filter_map(|result| result.as_ref().ok()?.usage()) {
+            self.usage.record(&self.tenant, usage);
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,556 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::cache::Caching;
+use crate::endpoints::{perplexity, sequence_log_likelihood, Usage, UsageReport};
+use crate::endpoints::request::{Endpoint, Streaming};
+
+/// Given a list of messages comprising a conversation, the model will return a response.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::chat::{ChatCompletion, ChatCompletionResponse, ChatMessage};
+/// use openai_rs::endpoints::ResponseError;
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let chat = ChatCompletion {
+///     model: Cow::Borrowed("gpt-4o-mini"),
+///     messages: vec![
+///         ChatMessage::system("You are a helpful assistant."),
+///         ChatMessage::user("What day of the week is it?"),
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let response: Result<ChatCompletionResponse, ResponseError> = client.create(
+///     None, &chat
+/// ).await;
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatCompletion<'a> {
+    /// ID of the model to use.
+    pub model: Cow<'a, str>,
+
+    /// A list of messages comprising the conversation so far.
+    pub messages: Vec<ChatMessage<'a>>,
+
+    /// What sampling temperature to use, between 0 and 2. Higher values like 0.8 will make the
+    /// output more random, while lower values like 0.2 will make it more focused and deterministic.
+    /// We generally recommend altering this or top_p but not both.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// An alternative to sampling with temperature, called nucleus sampling, where the model
+    /// considers the results of the tokens with top_p probability mass. So 0.1 means only
+    /// the tokens comprising the top 10% probability mass are considered.
+    /// We generally recommend altering this or temperature but not both.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// How many chat completion choices to generate for each input message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+
+    /// Whether to stream back partial progress.
+    /// If set, partial message deltas will be sent as data-only server-sent events
+    /// as they become available, with the stream terminated by a data: `[DONE]` message.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+
+    /// Options for streaming, e.g. to receive the usage in a final chunk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+
+    /// Up to 4 sequences where the API will stop generating further tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<Cow<'a, str>>>,
+
+    /// The maximum number of tokens that can be generated in the chat completion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+
+    /// Number between -2.0 and 2.0. Positive values penalize new tokens based on whether they
+    /// appear in the text so far, increasing the model's likelihood to talk about new topics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+
+    /// Number between -2.0 and 2.0.
+    /// Positive values penalize new tokens based on their existing frequency in the text so far,
+    /// decreasing the model's likelihood to repeat the same line verbatim.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+
+    /// Modify the likelihood of specified tokens appearing in the completion.
+    /// Accepts a json object that maps tokens (specified by their token ID in the tokenizer)
+    /// to an associated bias value from -100 to 100.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub logit_bias: HashMap<Cow<'a, str>, i32>,
+
+    /// Whether to return log probabilities of the output tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+
+    /// The number of most likely tokens to return at each token position (between 0 and 20).
+    /// `logprobs` must be set to true if this parameter is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+
+    /// If specified, the system will make a best effort to sample deterministically.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+
+    /// A list of tools the model may call. Currently, only functions are supported as a tool.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool<'a>>,
+
+    /// Controls which (if any) tool is called by the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice<'a>>,
+
+    /// A unique identifier representing your end-user, which will help OpenAI to monitor and detect abuse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<Cow<'a, str>>,
+}
+
+impl Default for ChatCompletion<'_> {
+    fn default() -> Self {
+        Self {
+            model: Cow::Borrowed(""),
+            messages: Vec::new(),
+            temperature: None,
+            top_p: None,
+            n: None,
+            stream: false,
+            stream_options: None,
+            stop: None,
+            max_tokens: None,
+            presence_penalty: None,
+            frequency_penalty: None,
+            logit_bias: HashMap::new(),
+            logprobs: None,
+            top_logprobs: None,
+            seed: None,
+            tools: Vec::new(),
+            tool_choice: None,
+            user: None
+        }
+    }
+}
+
+impl Endpoint for ChatCompletion<'_> {
+    const ENDPOINT: &'static str = "/chat/completions";
+
+    type Output = ChatCompletionResponse;
+
+    fn prompt(&self) -> Vec<Cow<'_, str>> {
+        self.messages.iter().filter_map(ChatMessage::text).collect()
+    }
+
+    fn max_tokens(&self) -> Option<u32> {
+        self.max_tokens.map(|max_tokens| max_tokens.saturating_mul(self.n.unwrap_or(1).max(1)))
+    }
+
+    fn caching(&self) -> Caching {
+        Caching::deterministic_if(self.temperature == Some(0.))
+    }
+}
+
+impl Streaming for ChatCompletion<'_> {
+    type Chunk = ChatCompletionChunk;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StreamOptions {
+    /// If set, an additional chunk with the usage of the whole request is streamed before `[DONE]`.
+    pub include_usage: bool,
+}
+
+/// A message of a conversation, tagged by the role of its author.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "role", rename_all = "lowercase")]
+pub enum ChatMessage<'a> {
+    /// Instructions which set the behavior of the assistant.
+    System {
+        content: Content<'a>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<Cow<'a, str>>,
+    },
+
+    /// A message of the end-user.
+    User {
+        content: Content<'a>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<Cow<'a, str>>,
+    },
+
+    /// A message of the model, which either has content or calls tools.
+    Assistant {
+        #[serde(default)]
+        content: Option<Content<'a>>,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<Cow<'a, str>>,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tool_calls: Vec<ToolCall<'a>>,
+
+        /// The refusal message of the model, if it refused to answer.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        refusal: Option<Cow<'a, str>>,
+    },
+
+    /// The result of a tool call of the assistant.
+    Tool {
+        content: Content<'a>,
+
+        tool_call_id: Cow<'a, str>,
+    },
+}
+
+impl<'a> ChatMessage<'a> {
+    /// Returns a system message with the given text.
+    pub fn system(content: impl Into<Cow<'a, str>>) -> Self {
+        Self::System { content: Content::Text(content.into()), name: None }
+    }
+
+    /// Returns a user message with the given text.
+    pub fn user(content: impl Into<Cow<'a, str>>) -> Self {
+        Self::User { content: Content::Text(content.into()), name: None }
+    }
+
+    /// Returns an assistant message with the given text.
+    pub fn assistant(content: impl Into<Cow<'a, str>>) -> Self {
+        Self::Assistant {
+            content: Some(Content::Text(content.into())),
+            name: None,
+            tool_calls: Vec::new(),
+            refusal: None
+        }
+    }
+
+    /// Returns a tool message with the result of the given tool call.
+    pub fn tool(tool_call_id: impl Into<Cow<'a, str>>, content: impl Into<Cow<'a, str>>) -> Self {
+        Self::Tool { content: Content::Text(content.into()), tool_call_id: tool_call_id.into() }
+    }
+
+    /// Returns the text of the message, joining the text parts of multi-part content.
+    ///
+    /// # Example
+    /// ```
+    /// use openai_rs::endpoints::chat::ChatMessage;
+    ///
+    /// assert_eq!(ChatMessage::user("Hello").text().as_deref(), Some("Hello"));
+    /// ```
+    pub fn text(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Self::System { content, .. }
+            | Self::User { content, .. }
+            | Self::Tool { content, .. } => Some(content.text()),
+            Self::Assistant { content, .. } => content.as_ref().map(Content::text),
+        }
+    }
+}
+
+/// The content of a message, either plain text or a list of parts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Content<'a> {
+    Text(Cow<'a, str>),
+    Parts(Vec<ContentPart<'a>>),
+}
+
+impl Content<'_> {
+    /// Returns the text of the content, joining the text parts by a line feed.
+    pub fn text(&self) -> Cow<'_, str> {
+        match self {
+            Self::Text(text) => Cow::Borrowed(text),
+            Self::Parts(parts) => Cow::Owned(parts.iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text { text } => Some(&**text),
+                    _ => None
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Content<'a> {
+    fn from(text: &'a str) -> Self {
+        Self::Text(Cow::Borrowed(text))
+    }
+}
+
+impl From<String> for Content<'_> {
+    fn from(text: String) -> Self {
+        Self::Text(Cow::Owned(text))
+    }
+}
+
+/// A part of multi-part content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart<'a> {
+    Text {
+        text: Cow<'a, str>,
+    },
+    ImageUrl {
+        image_url: ImageUrl<'a>,
+    },
+}
+
+/// An image referenced by a URL or a base64 encoded data URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageUrl<'a> {
+    pub url: Cow<'a, str>,
+
+    /// The detail level of the image, one of `auto`, `low` or `high`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<Cow<'a, str>>,
+}
+
+/// A tool the model may call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Tool<'a> {
+    Function {
+        function: FunctionDefinition<'a>,
+    },
+}
+
+/// The definition of a function the model may call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionDefinition<'a> {
+    /// The name of the function to be called.
+    pub name: Cow<'a, str>,
+
+    /// A description of what the function does, used by the model to choose when to call it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+
+    /// The parameters the function accepts, described as a JSON Schema object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// Controls which (if any) tool is called by the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolChoice<'a> {
+    /// One of `none`, `auto` or `required`.
+    Mode(Cow<'a, str>),
+
+    /// Forces the model to call the given tool.
+    Tool(ToolChoiceFunction<'a>),
+}
+
+/// Forces the model to call the function of the given name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolChoiceFunction<'a> {
+    Function {
+        function: FunctionName<'a>,
+    },
+}
+
+/// The name of a function.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionName<'a> {
+    pub name: Cow<'a, str>,
+}
+
+/// A call of a tool generated by the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall<'a> {
+    /// The ID of the tool call, referenced by the tool message of its result.
+    pub id: Cow<'a, str>,
+
+    #[serde(rename = "type")]
+    pub call_type: Cow<'a, str>,
+
+    pub function: FunctionCall<'a>,
+}
+
+/// The function the model called.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCall<'a> {
+    pub name: Cow<'a, str>,
+
+    /// The arguments to call the function with, as generated by the model in JSON format.
+    /// Note that the model does not always generate valid JSON, validate them before use.
+    pub arguments: Cow<'a, str>,
+}
+
+/// The response of a [`ChatCompletion`].
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::chat::{ChatCompletionResponse, ChatMessage};
+///
+/// let response: ChatCompletionResponse = serde_json::from_str(r#"{
+///     "id": "chatcmpl-123",
+///     "object": "chat.completion",
+///     "created": 1677652288,
+///     "model": "gpt-4o-mini",
+///     "choices": [{
+///         "index": 0,
+///         "message": { "role": "assistant", "content": "Hello there!" },
+///         "finish_reason": "stop"
+///     }],
+///     "usage": { "prompt_tokens": 9, "completion_tokens": 12, "total_tokens": 21 }
+/// }"#).unwrap();
+///
+/// assert_eq!(response.choices[0].message, ChatMessage::assistant("Hello there!"));
+/// assert_eq!(response.usage.unwrap().total_tokens, 21);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChatCompletionResponse {
+    pub id: String,
+    pub object: String,
+    pub created: u64,
+    pub model: String,
+    pub choices: Vec<ChatChoice>,
+    pub usage: Option<Usage>,
+    pub system_fingerprint: Option<String>,
+}
+
+impl UsageReport for ChatCompletionResponse {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChatChoice {
+    pub index: usize,
+    pub message: ChatMessage<'static>,
+    pub finish_reason: Option<String>,
+    pub logprobs: Option<ChoiceLogprobs>,
+}
+
+/// The log probabilities of the message tokens, returned if `logprobs` is requested.
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::chat::ChoiceLogprobs;
+///
+/// let logprobs: ChoiceLogprobs = serde_json::from_str(r#"{
+///     "content": [
+///         { "token": "Hi", "logprob": -0.25, "bytes": [72, 105], "top_logprobs": [] },
+///         { "token": "!", "logprob": -0.75, "bytes": [33], "top_logprobs": [] }
+///     ]
+/// }"#).unwrap();
+///
+/// assert_eq!(logprobs.sequence_log_likelihood(), -1.0);
+/// assert_eq!(logprobs.perplexity(), 0.5f64.exp());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ChoiceLogprobs {
+    /// The log probabilities of the content tokens.
+    pub content: Option<Vec<TokenLogprob>>,
+
+    /// The log probabilities of the refusal tokens.
+    pub refusal: Option<Vec<TokenLogprob>>,
+}
+
+impl ChoiceLogprobs {
+    /// Returns the log-likelihood of the content, the sum of the log probabilities of its tokens.
+    pub fn sequence_log_likelihood(&self) -> f64 {
+        sequence_log_likelihood(self.content_logprobs())
+    }
+
+    /// Returns the perplexity of the content, the exponential of the negative mean log probability.
+    /// Empty content has a perplexity of 1.
+    pub fn perplexity(&self) -> f64 {
+        perplexity(self.content_logprobs())
+    }
+
+    /// Returns the probability of each content token.
+    pub fn token_probabilities(&self) -> Vec<f64> {
+        self.content_logprobs().map(f64::exp).collect()
+    }
+
+    fn content_logprobs(&self) -> impl Iterator<Item = f64> + '_ {
+        self.content.iter().flatten().map(|token| token.logprob)
+    }
+}
+
+/// The log probability of a token and its most likely alternatives.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+
+    /// The UTF-8 bytes of the token, tokens may contain partial characters.
+    pub bytes: Option<Vec<u8>>,
+
+    /// The most likely tokens at this position, up to `top_logprobs` of the request.
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// An alternative token at a position with its log probability.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f64,
+    pub bytes: Option<Vec<u8>>,
+}
+
+/// A chunk of a streamed chat completion, see [`Client::create_stream`](crate::client::Client::create_stream).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub object: String,
+    pub created: u64,
+    pub model: String,
+    pub choices: Vec<ChatChunkChoice>,
+    pub usage: Option<Usage>,
+    pub system_fingerprint: Option<String>,
+}
+
+impl UsageReport for ChatCompletionChunk {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChatChunkChoice {
+    pub index: usize,
+    pub delta: ChatDelta,
+    pub finish_reason: Option<String>,
+    pub logprobs: Option<ChoiceLogprobs>,
+}
+
+/// The part of the message which was generated since the previous chunk.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ChatDelta {
+    pub role: Option<String>,
+    pub content: Option<String>,
+    pub refusal: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallDelta>,
+}
+
+/// The part of a tool call which was generated since the previous chunk.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ToolCallDelta {
+    pub index: usize,
+    pub id: Option<String>,
+    pub function: Option<FunctionCallDelta>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct FunctionCallDelta {
+    pub name: Option<String>,
+    pub arguments: Option<String>,
+}
@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
-use hyper::{Body, Request};
-use serde::Serialize;
-use crate::endpoints::Model;
+use serde::{Deserialize, Serialize};
+use crate::cache::Caching;
+use crate::endpoints::{SelectedExample, Usage, UsageReport};
+use crate::endpoints::models::ModelId;
 use crate::endpoints::request::Endpoint;
 
 /// Given a query and a set of labeled examples, the model will predict the most likely label for the query.
@@ -11,7 +12,7 @@ use crate::endpoints::request::Endpoint;
 pub struct Classification<'a> {
     /// ID of the engine to use for completion. You can select one of ada, babbage, curie, or davinci.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub model: Option<Model>,
+    pub model: Option<ModelId>,
 
     /// Query to be classified.
     pub query: Cow<'a, str>,
@@ -37,7 +38,7 @@ pub struct Classification<'a> {
 
     /// ID of the engine to use for Search. You can select one of ada, babbage, curie, or davinci
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub search_model: Option<Model>,
+    pub search_model: Option<ModelId>,
 
     /// What sampling temperature to use. Higher values mean the model will take more risks.
     /// Try 0.9 for more creative applications, and 0 (argmax sampling) for ones with a well-defined answer.
@@ -115,14 +116,37 @@ impl Default for Classification<'_> {
 }
 
 impl Endpoint for Classification<'_> {
-    const ENDPOINT: &'static str = "https://api.openai.com/v1/classifications";
+    const ENDPOINT: &'static str = "/classifications";
 
-    fn request(&self, auth_token: &str, _engine_id: Option<&str>) -> Request<Body> {
-        let serialized = serde_json::to_string(self)
-            .expect("Failed to serialize Classification");
-        let endpoint = Self::ENDPOINT.to_owned();
-        trace!("endpoint={}, serialized={}", endpoint, serialized);
+    type Output = ClassificationResponse;
 
-        super::request::post!(endpoint, auth_token, serialized)
+    fn input_text(&self) -> Vec<Cow<'_, str>> {
+        std::iter::once(&self.query)
+            .chain(self.examples.iter().flatten())
+            .chain(&self.labels)
+            .map(|text| Cow::Borrowed(&**text))
+            .collect()
     }
-}
\ No newline at end of file
+
+    fn caching(&self) -> Caching {
+        Caching::deterministic_if(self.temperature.unwrap_or_default() == 0.)
+    }
+}
+
+/// The response of a [`Classification`] with the predicted label.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ClassificationResponse {
+    pub object: String,
+    pub model: String,
+    pub search_model: ModelId,
+    pub completion: String,
+    pub label: String,
+    pub selected_examples: Vec<SelectedExample>,
+    pub usage: Option<Usage>,
+}
+
+impl UsageReport for ClassificationResponse {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
-use serde::Serialize;
-use hyper::{Body, Request};
-use crate::endpoints::request::Endpoint;
+use serde::{Deserialize, Serialize};
+use crate::cache::Caching;
+use crate::endpoints::{Choice, ResponseError, Usage, UsageReport};
+use crate::endpoints::request::{Endpoint, Streaming};
 
 /// Given a prompt, the response will return one or more predicted completions,
 /// and can also return the probabilities of alternative tokens at each position.
@@ -20,6 +21,8 @@ pub struct Completion<'a> {
     /// The maximum number of tokens to generate in the completion.
     /// The token count of your prompt plus max_tokens cannot exceed the model's context length.
     /// Most models have a context length of 2048 tokens (except for the newest models, which support 4096).
+    /// Prompts can be counted offline with `Tokenizer::count`
+    /// of the `tokenizer` feature.
     pub max_tokens: u32,
 
     /// What sampling temperature to use. Higher values means the model will take more risks.
@@ -78,8 +81,9 @@ pub struct Completion<'a> {
 
     /// Modify the likelihood of specified tokens appearing in the completion.
     /// Accepts a json object that maps tokens (specified by their token ID in the GPT tokenizer)
-    /// to an associated bias value from -100 to 100. You can use this tokenizer tool (which works
-    /// for both GPT-2 and GPT-3) to convert text to token IDs. Mathematically,
+    /// to an associated bias value from -100 to 100. You can use
+    /// `Tokenizer::encode` of the `tokenizer` feature
+    /// to convert text to token IDs. Mathematically,
     /// the bias is added to the logits generated by the model prior to sampling. The exact effect
     /// will vary per model, but values between -1 and 1 should decrease or increase likelihood
     /// of selection; values like -100 or 100 should result in a ban or exclusive selection
@@ -113,18 +117,45 @@ impl Default for Completion<'_> {
 }
 
 impl Endpoint for Completion<'_> {
-    const ENDPOINT: &'static str = "https://api.openai.com/v1/engines/{}/completions";
-
-    fn request(
-        &self,
-        auth_token: &str,
-        engine_id: Option<&str>
-    ) -> Request<Body> {
-        let endpoint = Self::ENDPOINT.replace("{}", engine_id.unwrap());
-        let serialized = serde_json::to_string(&self)
-            .expect("Failed to serialize request");
-        trace!("endpoint={}, serialized={}", endpoint, serialized);
-
-        super::request::post!(endpoint, auth_token, serialized)
+    const ENDPOINT: &'static str = "/engines/{}/completions";
+
+    type Output = CompletionResponse;
+
+    fn path(&self, engine_id: Option<&str>) -> Result<String, ResponseError> {
+        let engine_id = engine_id.ok_or(ResponseError::MissingEngine(Self::ENDPOINT))?;
+        Ok(Self::ENDPOINT.replace("{}", engine_id))
+    }
+
+    fn prompt(&self) -> Vec<Cow<'_, str>> {
+        self.prompt.iter().map(|prompt| Cow::Borrowed(&**prompt)).collect()
+    }
+
+    fn max_tokens(&self) -> Option<u32> {
+        Some(self.max_tokens.saturating_mul(self.n.max(self.best_of).max(1)))
+    }
+
+    fn caching(&self) -> Caching {
+        Caching::deterministic_if(self.temperature == 0.)
     }
-}
\ No newline at end of file
+}
+
+impl Streaming for Completion<'_> {
+    type Chunk = CompletionResponse;
+}
+
+/// The response of a [`Completion`], which is also the shape of each chunk of a streamed one.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CompletionResponse {
+    pub id: String,
+    pub object: String,
+    pub created: u64,
+    pub model: String,
+    pub choices: Vec<Choice>,
+    pub usage: Option<Usage>,
+}
+
+impl UsageReport for CompletionResponse {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
-use hyper::{Body, Request};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use crate::cache::Caching;
+use crate::endpoints::{Choice, ResponseError, Usage, UsageReport};
 use crate::endpoints::request::Endpoint;
 
 /// Given a prompt and an instruction, the model will return an edited version of the prompt.
@@ -36,14 +37,35 @@ impl Default for Edit<'_> {
 }
 
 impl Endpoint for Edit<'_> {
-    const ENDPOINT: &'static str = "https://api.openai.com/v1/engines/{}/edits";
+    const ENDPOINT: &'static str = "/engines/{}/edits";
 
-    fn request(&self, auth_token: &str, engine_id: Option<&str>) -> Request<Body> {
-        let endpoint = Self::ENDPOINT.replace("{}", engine_id.unwrap());
-        let serialized = serde_json::to_string(&self)
-            .expect("Failed to serialize Edit");
-        trace!("endpoint={}, serialized={}", endpoint, serialized);
+    type Output = EditResponse;
 
-        super::request::post!(endpoint, auth_token, serialized)
+    fn path(&self, engine_id: Option<&str>) -> Result<String, ResponseError> {
+        let engine_id = engine_id.ok_or(ResponseError::MissingEngine(Self::ENDPOINT))?;
+        Ok(Self::ENDPOINT.replace("{}", engine_id))
     }
-}
\ No newline at end of file
+
+    fn input_text(&self) -> Vec<Cow<'_, str>> {
+        vec![Cow::Borrowed(&*self.input), Cow::Borrowed(&*self.instruction)]
+    }
+
+    fn caching(&self) -> Caching {
+        Caching::deterministic_if(self.temperature == 0.)
+    }
+}
+
+/// The response of an [`Edit`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EditResponse {
+    pub object: String,
+    pub created: u64,
+    pub choices: Vec<Choice>,
+    pub usage: Option<Usage>,
+}
+
+impl UsageReport for EditResponse {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error;
+use crate::cache::Caching;
+use crate::endpoints::{Usage, UsageReport};
+use crate::endpoints::request::Endpoint;
+
+/// Creates an embedding vector representing the input text.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::embeddings::{Embedding, EmbeddingInput, EmbeddingResponse};
+/// use openai_rs::endpoints::ResponseError;
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let embedding = Embedding {
+///     model: Cow::Borrowed("text-embedding-3-small"),
+///     input: EmbeddingInput::Texts(vec![Cow::Borrowed("first"), Cow::Borrowed("second")]),
+///     dimensions: Some(256),
+///     ..Default::default()
+/// };
+///
+/// let response: Result<EmbeddingResponse, ResponseError> = client.create(
+///     None, &embedding
+/// ).await;
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Embedding<'a> {
+    /// ID of the model to use.
+    pub model: Cow<'a, str>,
+
+    /// Input text to embed, encoded as a string or array of tokens.
+    /// To embed multiple inputs in a single request, pass an array of strings or array of token arrays.
+    pub input: EmbeddingInput<'a>,
+
+    /// The format to return the embeddings in. Either of them is decoded into a vector of floats,
+    /// base64 reduces the size of the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding_format: Option<EncodingFormat>,
+
+    /// The number of dimensions the resulting output embeddings should have.
+    /// Only supported in `text-embedding-3` and later models.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+
+    /// A unique identifier representing your end-user, which will help OpenAI to monitor and detect abuse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<Cow<'a, str>>,
+}
+
+impl Default for Embedding<'_> {
+    fn default() -> Self {
+        Self {
+            model: Cow::Borrowed(""),
+            input: EmbeddingInput::Text(Cow::Borrowed("")),
+            encoding_format: None,
+            dimensions: None,
+            user: None
+        }
+    }
+}
+
+impl Endpoint for Embedding<'_> {
+    const ENDPOINT: &'static str = "/embeddings";
+
+    type Output = EmbeddingResponse;
+
+    fn input_text(&self) -> Vec<Cow<'_, str>> {
+        match &self.input {
+            EmbeddingInput::Text(text) => vec![Cow::Borrowed(&**text)],
+            EmbeddingInput::Texts(texts) => texts.iter().map(|text| Cow::Borrowed(&**text)).collect(),
+            EmbeddingInput::Tokens(_) | EmbeddingInput::TokenBatches(_) => Vec::new()
+        }
+    }
+
+    fn input_tokens(&self) -> u64 {
+        match &self.input {
+            EmbeddingInput::Tokens(tokens) => tokens.len() as u64,
+            EmbeddingInput::TokenBatches(batches) => batches.iter().map(|tokens| tokens.len() as u64).sum(),
+            EmbeddingInput::Text(_) | EmbeddingInput::Texts(_) => 0
+        }
+    }
+
+    fn caching(&self) -> Caching {
+        Caching::Deterministic
+    }
+}
+
+/// The input of an [`Embedding`], either a single or a batch of strings or token arrays.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum EmbeddingInput<'a> {
+    Text(Cow<'a, str>),
+    Texts(Vec<Cow<'a, str>>),
+    Tokens(Vec<u32>),
+    TokenBatches(Vec<Vec<u32>>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingFormat {
+    Float,
+    Base64,
+}
+
+/// The response of an [`Embedding`].
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::embeddings::EmbeddingResponse;
+///
+/// // [1.0, -2.5] as little-endian floats, encoded as base64.
+/// let response: EmbeddingResponse = serde_json::from_str(r#"{
+///     "object": "list",
+///     "data": [{ "object": "embedding", "index": 0, "embedding": "AACAPwAAIMA=" }],
+///     "model": "text-embedding-3-small",
+///     "usage": { "prompt_tokens": 8, "total_tokens": 8 }
+/// }"#).unwrap();
+///
+/// assert_eq!(response.data[0].embedding, vec![1.0, -2.5]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EmbeddingResponse {
+    pub object: String,
+    pub data: Vec<EmbeddingData>,
+    pub model: String,
+    pub usage: Usage,
+}
+
+impl UsageReport for EmbeddingResponse {
+    fn usage(&self) -> Option<&Usage> {
+        Some(&self.usage)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EmbeddingData {
+    pub object: String,
+
+    /// The index of the input this embedding belongs to.
+    pub index: usize,
+
+    /// The embedding vector, decoded from either encoding format.
+    #[serde(deserialize_with = "float_or_base64")]
+    pub embedding: Vec<f32>,
+}
+
+/// Deserializes an embedding which is either an array of floats
+/// or a base64 encoded string of little-endian floats.
+fn float_or_base64<'de, D>(deserializer: D) -> Result<Vec<f32>, D::Error>
+    where D: Deserializer<'de> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Encoded {
+        Float(Vec<f32>),
+        Base64(String),
+    }
+
+    match Encoded::deserialize(deserializer)? {
+        Encoded::Float(embedding) => Ok(embedding),
+        Encoded::Base64(encoded) => {
+            let bytes = STANDARD.decode(encoded).map_err(D::Error::custom)?;
+            if bytes.len() % 4 != 0 {
+                return Err(D::Error::custom("base64 embedding is not a multiple of 4 bytes"));
+            }
+
+            Ok(bytes.chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect())
+        }
+    }
+}
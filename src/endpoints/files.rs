@@ -0,0 +1,402 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+use hyper::body::Bytes;
+use hyper::Method;
+use serde::Deserialize;
+use tokio::io::AsyncRead;
+use crate::endpoints::{ResponseError, UsageReport};
+use crate::endpoints::multipart::Multipart;
+use crate::endpoints::request::{encode_path_segment, json, HttpEndpoint, RequestBody};
+
+pub use crate::endpoints::multipart::FileSource;
+
+/// Uploads a file that can be used across various endpoints, e.g. as `file` of a [`Search`].
+/// The file is sent as `multipart/form-data`, files and readers are streamed instead of being
+/// read into memory.
+///
+/// [`Search`]: crate::endpoints::search::Search
+///
+/// # Example
+/// ```
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::files::{FileObject, UploadFile};
+/// use openai_rs::endpoints::ResponseError;
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let upload = UploadFile::from_path("documents.jsonl", "search");
+/// let file: Result<FileObject, ResponseError> = client.create(None, &upload).await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct UploadFile<'a> {
+    /// The content of the file.
+    pub file: FileSource,
+
+    /// The name of the file, as it is reported in the [`FileObject`].
+    pub filename: Cow<'a, str>,
+
+    /// The intended purpose of the uploaded file, e.g. `fine-tune`, `batch`, `assistants`,
+    /// or `search`, `answers` and `classifications` for the legacy endpoints.
+    pub purpose: Cow<'a, str>,
+}
+
+impl<'a> UploadFile<'a> {
+    /// Returns an upload of the file at the path, named after the last component of the path.
+    /// The file is opened when the request is sent, so an upload can be retried.
+    pub fn from_path(path: impl Into<PathBuf>, purpose: impl Into<Cow<'a, str>>) -> Self {
+        let path = path.into();
+        let filename = path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self {
+            file: FileSource::Path(path),
+            filename: Cow::Owned(filename),
+            purpose: purpose.into(),
+        }
+    }
+
+    /// Returns an upload of the content in memory.
+    pub fn from_bytes(
+        filename: impl Into<Cow<'a, str>>,
+        content: impl Into<Bytes>,
+        purpose: impl Into<Cow<'a, str>>
+    ) -> Self {
+        Self {
+            file: FileSource::Bytes(content.into()),
+            filename: filename.into(),
+            purpose: purpose.into(),
+        }
+    }
+
+    /// Returns an upload of the content of the reader.
+    /// The reader is consumed by the first attempt, so the upload is sent once without retries.
+    ///
+    /// # Example
+    /// ```
+    /// use hyper::StatusCode;
+    /// use openai_rs::endpoints::files::UploadFile;
+    /// use openai_rs::endpoints::ResponseError;
+    /// use openai_rs::openai;
+    /// use openai_rs::retry::RetryPolicy;
+    /// use openai_rs::transport::{MockResponse, MockTransport};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let transport = MockTransport::new();
+    /// transport.push(MockResponse::json(StatusCode::OK, &serde_json::json!({
+    ///     "id": "file-abc123",
+    ///     "object": "file",
+    ///     "bytes": 16,
+    ///     "created_at": 1613779121,
+    ///     "filename": "train.jsonl",
+    ///     "purpose": "fine-tune"
+    /// })));
+    /// let client = openai::builder("api_key").transport(transport.clone()).build().unwrap();
+    ///
+    /// let reader = tokio::io::BufReader::new(&b"{\"prompt\": \"\"}\n\n"[..]);
+    /// let upload = UploadFile::from_reader("train.jsonl", reader, "fine-tune");
+    /// let file = client.create(None, &upload).await.unwrap();
+    /// assert_eq!(file.id, "file-abc123");
+    ///
+    /// let body = String::from_utf8(transport.requests()[0].body.to_vec()).unwrap();
+    /// assert!(body.contains("name=\"file\"; filename=\"train.jsonl\""));
+    /// assert!(body.contains("{\"prompt\": \"\"}\n\n"));
+    ///
+    /// // The reader was consumed by the first request.
+    /// assert!(client.create(None, &upload).await.is_err());
+    ///
+    /// // A failed upload is not retried, but fails with the error of the API.
+    /// let transport = MockTransport::new();
+    /// transport
+    ///     .push(MockResponse::json(StatusCode::TOO_MANY_REQUESTS, &serde_json::json!({
+    ///         "error": { "message": "Rate limit reached", "type": "requests" }
+    ///     })).header("retry-after", "1"))
+    ///     .push(MockResponse::json(StatusCode::OK, &serde_json::json!({})));
+    /// let client = openai::builder("api_key")
+    ///     .transport(transport.clone())
+    ///     .retry_policy(RetryPolicy::default())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let reader = tokio::io::BufReader::new(&b"{\"prompt\": \"\"}\n"[..]);
+    /// let upload = UploadFile::from_reader("train.jsonl", reader, "fine-tune");
+    /// let error = client.create(None, &upload).await.unwrap_err();
+    /// assert!(matches!(error, ResponseError::Api(error) if error.status == StatusCode::TOO_MANY_REQUESTS));
+    /// assert_eq!(transport.remaining(), 1);
+    /// # }
+    /// ```
+    pub fn from_reader<R>(
+        filename: impl Into<Cow<'a, str>>,
+        reader: R,
+        purpose: impl Into<Cow<'a, str>>
+    ) -> Self
+        where R: AsyncRead + Send + Unpin + 'static {
+        Self {
+            file: FileSource::reader(reader),
+            filename: filename.into(),
+            purpose: purpose.into(),
+        }
+    }
+}
+
+impl HttpEndpoint for UploadFile<'_> {
+    type Output = FileObject;
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/files".to_owned())
+    }
+
+    fn body(&self) -> Result<RequestBody, ResponseError> {
+        let form = Multipart::new()
+            .text("purpose", &self.purpose)
+            .file("file", &self.filename, &self.file)?;
+
+        Ok(RequestBody::Multipart(form))
+    }
+
+    fn replayable(&self) -> bool {
+        self.file.is_replayable()
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Lists the files which belong to the organization, optionally only those of a purpose.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use hyper::{Method, StatusCode};
+/// use openai_rs::endpoints::files::ListFiles;
+/// use openai_rs::openai;
+/// use openai_rs::transport::{MockResponse, MockTransport};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let transport = MockTransport::new();
+/// transport.push(MockResponse::json(StatusCode::OK, &serde_json::json!({
+///     "object": "list",
+///     "data": []
+/// })));
+/// let client = openai::builder("api_key").transport(transport.clone()).build().unwrap();
+///
+/// let list = ListFiles { purpose: Some(Cow::Borrowed("fine-tune")) };
+/// let files = client.create(None, &list).await.unwrap();
+/// assert!(files.data.is_empty());
+///
+/// let request = &transport.requests()[0];
+/// assert_eq!(request.method, Method::GET);
+/// assert_eq!(request.uri, "https://api.openai.com/v1/files?purpose=fine-tune");
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ListFiles<'a> {
+    /// Only return files with the given purpose.
+    pub purpose: Option<Cow<'a, str>>,
+}
+
+impl HttpEndpoint for ListFiles<'_> {
+    type Output = FileList;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/files".to_owned())
+    }
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        self.purpose.iter()
+            .map(|purpose| ("purpose", purpose.to_string()))
+            .collect()
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Returns the metadata of a file.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use hyper::StatusCode;
+/// use openai_rs::endpoints::files::RetrieveFile;
+/// use openai_rs::openai;
+/// use openai_rs::transport::{MockResponse, MockTransport};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let transport = MockTransport::new();
+/// transport.push(MockResponse::json(StatusCode::OK, &serde_json::json!({
+///     "id": "file-abc123",
+///     "object": "file",
+///     "bytes": 16,
+///     "created_at": 1613779121,
+///     "filename": "train.jsonl",
+///     "purpose": "fine-tune"
+/// })));
+/// let client = openai::builder("api_key").transport(transport.clone()).build().unwrap();
+///
+/// // The id is percent-encoded, so it cannot change the target of the request.
+/// let retrieve = RetrieveFile { file_id: Cow::Borrowed("../batches?limit=1#") };
+/// client.create(None, &retrieve).await.unwrap();
+/// assert_eq!(transport.requests()[0].uri, "https://api.openai.com/v1/files/..%2Fbatches%3Flimit%3D1%23");
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct RetrieveFile<'a> {
+    /// The ID of the file.
+    pub file_id: Cow<'a, str>,
+}
+
+impl HttpEndpoint for RetrieveFile<'_> {
+    type Output = FileObject;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/files/{}", encode_path_segment(&self.file_id)))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Returns the content of a file.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::files::DownloadFile;
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let download = DownloadFile { file_id: Cow::Borrowed("file-XjGxS3KTG0uNmNOK362iJua3") };
+/// let content = client.create(None, &download).await.unwrap();
+/// std::fs::write("results.jsonl", content).unwrap();
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct DownloadFile<'a> {
+    /// The ID of the file.
+    pub file_id: Cow<'a, str>,
+}
+
+impl HttpEndpoint for DownloadFile<'_> {
+    type Output = Bytes;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/files/{}/content", encode_path_segment(&self.file_id)))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        Ok(body)
+    }
+}
+
+/// Deletes a file.
+#[derive(Debug, Default, Clone)]
+pub struct DeleteFile<'a> {
+    /// The ID of the file.
+    pub file_id: Cow<'a, str>,
+}
+
+impl HttpEndpoint for DeleteFile<'_> {
+    type Output = DeletedFile;
+
+    fn method(&self) -> Method {
+        Method::DELETE
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/files/{}", encode_path_segment(&self.file_id)))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// An uploaded file.
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::files::FileObject;
+///
+/// let file: FileObject = serde_json::from_str(r#"{
+///     "id": "file-XjGxS3KTG0uNmNOK362iJua3",
+///     "object": "file",
+///     "bytes": 140,
+///     "created_at": 1613779121,
+///     "filename": "documents.jsonl",
+///     "purpose": "search"
+/// }"#).unwrap();
+///
+/// assert_eq!(file.bytes, 140);
+/// assert_eq!(file.status, None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FileObject {
+    pub id: String,
+    pub object: String,
+
+    /// The size of the file in bytes.
+    pub bytes: u64,
+
+    /// The Unix timestamp (in seconds) of the upload.
+    pub created_at: u64,
+    pub filename: String,
+    pub purpose: String,
+
+    /// The processing status of the file, e.g. `uploaded`, `processed` or `error`.
+    #[serde(default)]
+    pub status: Option<String>,
+
+    /// The reason why the processing of the file failed.
+    #[serde(default)]
+    pub status_details: Option<String>,
+}
+
+impl UsageReport for FileObject {}
+
+/// The response of [`ListFiles`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FileList {
+    pub object: String,
+    pub data: Vec<FileObject>,
+
+    /// Whether there are more files than returned.
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl UsageReport for FileList {}
+
+/// The response of [`DeleteFile`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeletedFile {
+    pub id: String,
+    pub object: String,
+    pub deleted: bool,
+}
+
+impl UsageReport for DeletedFile {}
@@ -0,0 +1,576 @@
+use std::borrow::Cow;
+use std::time::Duration;
+use futures_util::stream::{self, Stream, StreamExt};
+use hyper::body::Bytes;
+use hyper::Method;
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::client::Client;
+use crate::endpoints::{ResponseError, UsageReport};
+use crate::endpoints::request::{encode_path_segment, json, Endpoint, HttpEndpoint};
+
+/// The number of events which are requested per poll of a job.
+const EVENTS_PER_POLL: u32 = 100;
+
+/// Creates a fine-tuning job which begins the process of creating a new model from a given dataset.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use std::time::Duration;
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::fine_tuning::CreateFineTuningJob;
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let job = client.create(None, &CreateFineTuningJob {
+///     model: Cow::Borrowed("gpt-4o-mini-2024-07-18"),
+///     training_file: Cow::Borrowed("file-abc123"),
+///     suffix: Some(Cow::Borrowed("support")),
+///     ..Default::default()
+/// }).await.unwrap();
+///
+/// let model = client.wait_for_fine_tuning(&job.id, Duration::from_secs(30)).await.unwrap();
+/// println!("fine-tuned {}", model);
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateFineTuningJob<'a> {
+    /// The name of the model to fine-tune.
+    pub model: Cow<'a, str>,
+
+    /// The ID of an uploaded file with the purpose `fine-tune` that contains the training data.
+    pub training_file: Cow<'a, str>,
+
+    /// The ID of an uploaded file that contains the validation data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation_file: Option<Cow<'a, str>>,
+
+    /// The hyperparameters of the job. Unset hyperparameters are chosen by the API.
+    #[serde(skip_serializing_if = "Hyperparameters::is_auto")]
+    pub hyperparameters: Hyperparameters,
+
+    /// A string of up to 64 characters that will be added to the name of the fine-tuned model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<Cow<'a, str>>,
+
+    /// The seed controls the reproducibility of the job.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl Default for CreateFineTuningJob<'_> {
+    fn default() -> Self {
+        Self {
+            model: Cow::Borrowed(""),
+            training_file: Cow::Borrowed(""),
+            validation_file: None,
+            hyperparameters: Hyperparameters::default(),
+            suffix: None,
+            seed: None
+        }
+    }
+}
+
+impl Endpoint for CreateFineTuningJob<'_> {
+    const ENDPOINT: &'static str = "/fine_tuning/jobs";
+
+    type Output = FineTuningJob;
+}
+
+/// The hyperparameters of a fine-tuning job, `None` stands for `auto`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hyperparameters {
+    /// The number of epochs to train the model for.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "number_or_auto")]
+    pub n_epochs: Option<u32>,
+
+    /// The number of examples in each batch.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "number_or_auto")]
+    pub batch_size: Option<u32>,
+
+    /// The scaling factor for the learning rate.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "number_or_auto")]
+    pub learning_rate_multiplier: Option<f64>,
+}
+
+impl Hyperparameters {
+    /// Returns whether all hyperparameters are chosen by the API.
+    pub fn is_auto(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Lists the fine-tuning jobs of the organization, most recent first.
+#[derive(Debug, Default, Clone)]
+pub struct ListFineTuningJobs<'a> {
+    /// The ID of the last job of the previous page.
+    pub after: Option<Cow<'a, str>>,
+
+    /// The number of jobs to retrieve, 20 by default.
+    pub limit: Option<u32>,
+}
+
+impl HttpEndpoint for ListFineTuningJobs<'_> {
+    type Output = FineTuningJobList;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/fine_tuning/jobs".to_owned())
+    }
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        page_query(&self.after, self.limit)
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Returns a fine-tuning job.
+#[derive(Debug, Default, Clone)]
+pub struct RetrieveFineTuningJob<'a> {
+    /// The ID of the job.
+    pub job_id: Cow<'a, str>,
+}
+
+impl HttpEndpoint for RetrieveFineTuningJob<'_> {
+    type Output = FineTuningJob;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/fine_tuning/jobs/{}", encode_path_segment(&self.job_id)))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Immediately cancels a fine-tuning job.
+#[derive(Debug, Default, Clone)]
+pub struct CancelFineTuningJob<'a> {
+    /// The ID of the job.
+    pub job_id: Cow<'a, str>,
+}
+
+impl HttpEndpoint for CancelFineTuningJob<'_> {
+    type Output = FineTuningJob;
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/fine_tuning/jobs/{}/cancel", encode_path_segment(&self.job_id)))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Lists the status updates of a fine-tuning job, most recent first.
+#[derive(Debug, Default, Clone)]
+pub struct ListFineTuningEvents<'a> {
+    /// The ID of the job.
+    pub job_id: Cow<'a, str>,
+
+    /// The ID of the last event of the previous page.
+    pub after: Option<Cow<'a, str>>,
+
+    /// The number of events to retrieve, 20 by default.
+    pub limit: Option<u32>,
+}
+
+impl HttpEndpoint for ListFineTuningEvents<'_> {
+    type Output = FineTuningEventList;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/fine_tuning/jobs/{}/events", encode_path_segment(&self.job_id)))
+    }
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        page_query(&self.after, self.limit)
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Lists the checkpoints of a fine-tuning job, which are created at the end of each epoch.
+#[derive(Debug, Default, Clone)]
+pub struct ListFineTuningCheckpoints<'a> {
+    /// The ID of the job.
+    pub job_id: Cow<'a, str>,
+
+    /// The ID of the last checkpoint of the previous page.
+    pub after: Option<Cow<'a, str>>,
+
+    /// The number of checkpoints to retrieve, 10 by default.
+    pub limit: Option<u32>,
+}
+
+impl HttpEndpoint for ListFineTuningCheckpoints<'_> {
+    type Output = FineTuningCheckpointList;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/fine_tuning/jobs/{}/checkpoints", encode_path_segment(&self.job_id)))
+    }
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        page_query(&self.after, self.limit)
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// A fine-tuning job.
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::fine_tuning::{FineTuningJob, JobStatus};
+///
+/// let job: FineTuningJob = serde_json::from_str(r#"{
+///     "object": "fine_tuning.job",
+///     "id": "ftjob-abc123",
+///     "model": "gpt-4o-mini-2024-07-18",
+///     "created_at": 1721764800,
+///     "finished_at": 1721767200,
+///     "fine_tuned_model": "ft:gpt-4o-mini:org:support:7p4lURel",
+///     "organization_id": "org-123",
+///     "result_files": ["file-abc123"],
+///     "status": "succeeded",
+///     "validation_file": null,
+///     "training_file": "file-abc123",
+///     "hyperparameters": { "n_epochs": 4, "batch_size": "auto", "learning_rate_multiplier": 1.8 },
+///     "trained_tokens": 5768
+/// }"#).unwrap();
+///
+/// assert_eq!(job.status, JobStatus::Succeeded);
+/// assert_eq!(job.hyperparameters.n_epochs, Some(4));
+/// assert_eq!(job.hyperparameters.batch_size, None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FineTuningJob {
+    pub id: String,
+    pub object: String,
+
+    /// The base model which is fine-tuned.
+    pub model: String,
+
+    /// The Unix timestamp (in seconds) of the creation of the job.
+    pub created_at: u64,
+
+    /// The Unix timestamp (in seconds) of the end of the job, if it ended.
+    #[serde(default)]
+    pub finished_at: Option<u64>,
+
+    /// The name of the fine-tuned model, once the job succeeded.
+    #[serde(default)]
+    pub fine_tuned_model: Option<String>,
+
+    #[serde(default)]
+    pub organization_id: Option<String>,
+
+    /// The IDs of the result files of the job.
+    #[serde(default)]
+    pub result_files: Vec<String>,
+    pub status: JobStatus,
+    pub training_file: String,
+
+    #[serde(default)]
+    pub validation_file: Option<String>,
+
+    #[serde(default)]
+    pub hyperparameters: Hyperparameters,
+
+    /// The number of billable tokens processed by the job, once it succeeded.
+    #[serde(default)]
+    pub trained_tokens: Option<u64>,
+
+    /// The reason why the job failed.
+    #[serde(default)]
+    pub error: Option<JobError>,
+
+    /// The estimated Unix timestamp (in seconds) of the end of a running job.
+    #[serde(default)]
+    pub estimated_finish: Option<u64>,
+
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl UsageReport for FineTuningJob {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    ValidatingFiles,
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+
+    /// A status which is not known to this crate.
+    #[serde(other)]
+    Unknown,
+}
+
+impl JobStatus {
+    /// Returns whether the job ended and its status will not change anymore.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct JobError {
+    pub code: Option<String>,
+    pub message: String,
+    pub param: Option<String>,
+}
+
+/// The response of [`ListFineTuningJobs`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FineTuningJobList {
+    pub object: String,
+    pub data: Vec<FineTuningJob>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl UsageReport for FineTuningJobList {}
+
+/// A status update of a fine-tuning job.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FineTuningEvent {
+    pub id: String,
+    pub object: String,
+
+    /// The Unix timestamp (in seconds) of the event.
+    pub created_at: u64,
+
+    /// The level of the event, e.g. `info`, `warn` or `error`.
+    pub level: String,
+    pub message: String,
+
+    /// The type of the event, e.g. `message` or `metrics`.
+    #[serde(rename = "type", default)]
+    pub event_type: Option<String>,
+
+    /// The data of a `metrics` event, e.g. the training loss of a step.
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+/// The response of [`ListFineTuningEvents`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FineTuningEventList {
+    pub object: String,
+    pub data: Vec<FineTuningEvent>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl UsageReport for FineTuningEventList {}
+
+/// A model checkpoint of a fine-tuning job, which can be used like a fine-tuned model.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FineTuningCheckpoint {
+    pub id: String,
+    pub object: String,
+
+    /// The Unix timestamp (in seconds) of the creation of the checkpoint.
+    pub created_at: u64,
+
+    /// The name of the model of the checkpoint.
+    pub fine_tuned_model_checkpoint: String,
+    pub fine_tuning_job_id: String,
+
+    /// The step at which the checkpoint was created.
+    pub step_number: u64,
+
+    /// The metrics of the step, e.g. `train_loss` or `valid_mean_token_accuracy`.
+    #[serde(default)]
+    pub metrics: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The response of [`ListFineTuningCheckpoints`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FineTuningCheckpointList {
+    pub object: String,
+    pub data: Vec<FineTuningCheckpoint>,
+    #[serde(default)]
+    pub first_id: Option<String>,
+    #[serde(default)]
+    pub last_id: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl UsageReport for FineTuningCheckpointList {}
+
+impl Client {
+    /// Returns a stream of the events of the fine-tuning job in chronological order,
+    /// polling the job every `poll_interval`. The stream ends once the job reached a terminal
+    /// state or a request failed.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use futures_util::StreamExt;
+    /// use hyper::StatusCode;
+    /// use openai_rs::openai;
+    /// use openai_rs::transport::{MockResponse, MockTransport};
+    /// use serde_json::json;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let job = |status: &str, model: Option<&str>| MockResponse::json(StatusCode::OK, &json!({
+    ///     "object": "fine_tuning.job", "id": "ftjob-abc123", "model": "gpt-4o-mini",
+    ///     "created_at": 0, "status": status, "training_file": "file-abc123",
+    ///     "fine_tuned_model": model
+    /// }));
+    /// let event = |id: &str, message: &str| json!({
+    ///     "object": "fine_tuning.job.event", "id": id, "created_at": 0,
+    ///     "level": "info", "message": message
+    /// });
+    ///
+    /// let transport = MockTransport::new();
+    /// transport
+    ///     .push(job("running", None))
+    ///     .push(MockResponse::json(StatusCode::OK, &json!({
+    ///         "object": "list", "data": [event("2", "Fine-tuning job started")], "has_more": true
+    ///     })))
+    ///     .push(MockResponse::json(StatusCode::OK, &json!({
+    ///         "object": "list", "data": [event("1", "Validating")], "has_more": false
+    ///     })))
+    ///     .push(job("succeeded", Some("ft:gpt-4o-mini:org::abc")))
+    ///     .push(MockResponse::json(StatusCode::OK, &json!({
+    ///         "object": "list", "data": [event("3", "The job has successfully completed"), event("2", "Fine-tuning job started")],
+    ///         "has_more": true
+    ///     })));
+    /// let client = openai::builder("api_key").transport(transport.clone()).build().unwrap();
+    ///
+    /// let messages: Vec<String> = client.fine_tuning_events("ftjob-abc123", Duration::from_millis(1))
+    ///     .map(|event| event.unwrap().message)
+    ///     .collect().await;
+    /// assert_eq!(messages, ["Validating", "Fine-tuning job started", "The job has successfully completed"]);
+    /// assert_eq!(transport.remaining(), 0);
+    /// assert!(transport.requests()[2].uri.to_string().contains("after=2"));
+    /// # }
+    /// ```
+    pub fn fine_tuning_events<'a>(
+        &'a self,
+        job_id: &'a str,
+        poll_interval: Duration
+    ) -> impl Stream<Item = Result<FineTuningEvent, ResponseError>> + 'a {
+        // The ID of the most recent event which was yielded, older events are skipped.
+        let polling: Option<(Option<String>, bool)> = Some((None, true));
+
+        stream::unfold(polling, move |polling| async move {
+            let (last, first) = polling?;
+            if !first {
+                tokio::time::sleep(poll_interval).await;
+            }
+
+            // The job is retrieved before its events, so the events of a terminated job are complete.
+            let job = match self.create(None, &RetrieveFineTuningJob { job_id: Cow::Borrowed(job_id) }).await {
+                Ok(job) => job,
+                Err(error) => return Some((vec![Err(error)], None))
+            };
+
+            // The events are listed most recent first, so pages are requested until a yielded event is reached.
+            let mut new = Vec::new();
+            loop {
+                let page = ListFineTuningEvents {
+                    job_id: Cow::Borrowed(job_id),
+                    after: new.last().map(|event: &FineTuningEvent| Cow::Owned(event.id.clone())),
+                    limit: Some(EVENTS_PER_POLL)
+                };
+                let page = match self.create(None, &page).await {
+                    Ok(page) => page,
+                    Err(error) => return Some((vec![Err(error)], None))
+                };
+
+                let reached = page.data.iter().any(|event| Some(&event.id) == last.as_ref());
+                let done = reached || !page.has_more || page.data.is_empty();
+                new.extend(page.data.into_iter().take_while(|event| Some(&event.id) != last.as_ref()));
+                if done {
+                    break;
+                }
+            }
+
+            let last = new.first().map(|event| event.id.clone()).or(last);
+            let polling = if job.status.is_terminal() { None } else { Some((last, false)) };
+            Some((new.into_iter().rev().map(Ok).collect::<Vec<_>>(), polling))
+        }).flat_map(stream::iter)
+    }
+
+    /// Polls the fine-tuning job every `poll_interval` until it reached a terminal state
+    /// and returns the name of the fine-tuned model. The events of the job are logged meanwhile.
+    /// Fails with [`ResponseError::FineTuningFailed`] if the job failed or was cancelled.
+    pub async fn wait_for_fine_tuning(
+        &self,
+        job_id: &str,
+        poll_interval: Duration
+    ) -> Result<String, ResponseError> {
+        let mut events = Box::pin(self.fine_tuning_events(job_id, poll_interval));
+        while let Some(event) = events.next().await {
+            let event = event?;
+            debug!("{} [{}]: {}", job_id, event.level, event.message);
+        }
+
+        let job = self.create(None, &RetrieveFineTuningJob { job_id: Cow::Borrowed(job_id) }).await?;
+        match (job.status, &job.fine_tuned_model) {
+            (JobStatus::Succeeded, Some(model)) => Ok(model.clone()),
+            _ => Err(ResponseError::FineTuningFailed(Box::new(job)))
+        }
+    }
+}
+
+/// Returns the query string parameters of a paginated list.
+fn page_query(after: &Option<Cow<str>>, limit: Option<u32>) -> Vec<(&'static str, String)> {
+    let mut query = Vec::new();
+    if let Some(after) = after {
+        query.push(("after", after.to_string()));
+    }
+    if let Some(limit) = limit {
+        query.push(("limit", limit.to_string()));
+    }
+
+    query
+}
+
+/// Deserializes a hyperparameter which is either a number or `"auto"`.
+fn number_or_auto<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where D: Deserializer<'de>, T: Deserialize<'de> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrAuto<T> {
+        Number(T),
+        Auto(serde::de::IgnoredAny),
+    }
+
+    Ok(match Option::<NumberOrAuto<T>>::deserialize(deserializer)? {
+        Some(NumberOrAuto::Number(number)) => Some(number),
+        _ => None
+    })
+}
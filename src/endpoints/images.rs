@@ -0,0 +1,506 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hyper::body::Bytes;
+use hyper::{Body, Method, Request};
+use serde::{Deserialize, Serialize};
+use crate::client::{timeout, Client};
+use crate::endpoints::{ResponseError, Usage, UsageReport};
+use crate::endpoints::multipart::{FileSource, Multipart};
+use crate::endpoints::request::{json, Endpoint, HttpEndpoint, RequestBody};
+
+/// Creates images given a prompt.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::images::{ImageGeneration, ImageResponseFormat};
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let generation = ImageGeneration {
+///     prompt: Cow::Borrowed("A watercolor lighthouse at dawn"),
+///     model: Some(Cow::Borrowed("dall-e-3")),
+///     size: Some(Cow::Borrowed("1024x1024")),
+///     response_format: Some(ImageResponseFormat::B64Json),
+///     ..Default::default()
+/// };
+///
+/// let response = client.create(None, &generation).await.unwrap();
+/// let paths = client.save_images(&response, "designs", "lighthouse").await.unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageGeneration<'a> {
+    /// A text description of the desired image(s).
+    pub prompt: Cow<'a, str>,
+
+    /// The model to use for image generation, e.g. `dall-e-2` or `dall-e-3`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<Cow<'a, str>>,
+
+    /// The number of images to generate. `dall-e-3` only supports 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+
+    /// The quality of the image, e.g. `standard` or `hd` for `dall-e-3`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<Cow<'a, str>>,
+
+    /// The format in which the generated images are returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ImageResponseFormat>,
+
+    /// The file format of the generated images, only supported by `gpt-image-1`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<ImageOutputFormat>,
+
+    /// The size of the generated images, e.g. `256x256`, `512x512` or `1024x1024`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<Cow<'a, str>>,
+
+    /// The style of the generated images, either `vivid` or `natural` for `dall-e-3`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Cow<'a, str>>,
+
+    /// A unique identifier representing your end-user, which will help OpenAI to monitor and detect abuse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<Cow<'a, str>>,
+}
+
+impl Default for ImageGeneration<'_> {
+    fn default() -> Self {
+        Self {
+            prompt: Cow::Borrowed(""),
+            model: None,
+            n: None,
+            quality: None,
+            response_format: None,
+            output_format: None,
+            size: None,
+            style: None,
+            user: None
+        }
+    }
+}
+
+impl Endpoint for ImageGeneration<'_> {
+    const ENDPOINT: &'static str = "/images/generations";
+
+    type Output = ImagesResponse;
+
+    fn prompt(&self) -> Vec<Cow<'_, str>> {
+        vec![Cow::Borrowed(&*self.prompt)]
+    }
+}
+
+/// Creates edited or extended images given an original image and a prompt.
+/// The transparent areas of the mask, or of the image if no mask is given, are edited.
+///
+/// # Example
+/// ```
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::images::{ImageEdit, ImageFile};
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let edit = ImageEdit {
+///     mask: Some(ImageFile::from_path("mask.png")),
+///     ..ImageEdit::from_path("room.png", "A sunlit indoor lounge area with a pool")
+/// };
+///
+/// let response = client.create(None, &edit).await.unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ImageEdit<'a> {
+    /// The image to edit, a square PNG file of less than 4MB.
+    pub image: ImageFile<'a>,
+
+    /// An image whose transparent areas indicate where the image should be edited.
+    /// It must have the same dimensions as the image.
+    pub mask: Option<ImageFile<'a>>,
+
+    /// A text description of the desired image(s).
+    pub prompt: Cow<'a, str>,
+
+    /// The model to use for image editing.
+    pub model: Option<Cow<'a, str>>,
+
+    /// The number of images to generate.
+    pub n: Option<u32>,
+
+    /// The format in which the generated images are returned.
+    pub response_format: Option<ImageResponseFormat>,
+
+    /// The file format of the generated images, only supported by `gpt-image-1`.
+    pub output_format: Option<ImageOutputFormat>,
+
+    /// The size of the generated images, e.g. `256x256`, `512x512` or `1024x1024`.
+    pub size: Option<Cow<'a, str>>,
+
+    /// A unique identifier representing your end-user, which will help OpenAI to monitor and detect abuse.
+    pub user: Option<Cow<'a, str>>,
+}
+
+impl<'a> ImageEdit<'a> {
+    /// Returns an edit of the image file at the path.
+    pub fn from_path(path: impl Into<PathBuf>, prompt: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            image: ImageFile::from_path(path),
+            mask: None,
+            prompt: prompt.into(),
+            model: None,
+            n: None,
+            response_format: None,
+            output_format: None,
+            size: None,
+            user: None
+        }
+    }
+}
+
+impl HttpEndpoint for ImageEdit<'_> {
+    type Output = ImagesResponse;
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/images/edits".to_owned())
+    }
+
+    fn body(&self) -> Result<RequestBody, ResponseError> {
+        let mut form = Multipart::new()
+            .file("image", &self.image.filename, &self.image.file)?;
+        if let Some(mask) = &self.mask {
+            form = form.file("mask", &mask.filename, &mask.file)?;
+        }
+
+        let form = form
+            .text("prompt", &self.prompt)
+            .optional("model", self.model.as_ref())
+            .optional("n", self.n)
+            .optional("response_format", self.response_format)
+            .optional("output_format", self.output_format)
+            .optional("size", self.size.as_ref())
+            .optional("user", self.user.as_ref());
+
+        Ok(RequestBody::Multipart(form))
+    }
+
+    fn replayable(&self) -> bool {
+        self.image.file.is_replayable() && self.mask.as_ref().is_none_or(|mask| mask.file.is_replayable())
+    }
+
+    fn prompt(&self) -> Vec<Cow<'_, str>> {
+        vec![Cow::Borrowed(&*self.prompt)]
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Creates variations of a given image.
+#[derive(Debug)]
+pub struct ImageVariation<'a> {
+    /// The image to use as the basis for the variations, a square PNG file of less than 4MB.
+    pub image: ImageFile<'a>,
+
+    /// The model to use for image variations.
+    pub model: Option<Cow<'a, str>>,
+
+    /// The number of images to generate.
+    pub n: Option<u32>,
+
+    /// The format in which the generated images are returned.
+    pub response_format: Option<ImageResponseFormat>,
+
+    /// The size of the generated images, e.g. `256x256`, `512x512` or `1024x1024`.
+    pub size: Option<Cow<'a, str>>,
+
+    /// A unique identifier representing your end-user, which will help OpenAI to monitor and detect abuse.
+    pub user: Option<Cow<'a, str>>,
+}
+
+impl<'a> ImageVariation<'a> {
+    /// Returns variations of the image file at the path.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            image: ImageFile::from_path(path),
+            model: None,
+            n: None,
+            response_format: None,
+            size: None,
+            user: None
+        }
+    }
+}
+
+impl HttpEndpoint for ImageVariation<'_> {
+    type Output = ImagesResponse;
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/images/variations".to_owned())
+    }
+
+    fn body(&self) -> Result<RequestBody, ResponseError> {
+        let form = Multipart::new()
+            .file("image", &self.image.filename, &self.image.file)?
+            .optional("model", self.model.as_ref())
+            .optional("n", self.n)
+            .optional("response_format", self.response_format)
+            .optional("size", self.size.as_ref())
+            .optional("user", self.user.as_ref());
+
+        Ok(RequestBody::Multipart(form))
+    }
+
+    fn replayable(&self) -> bool {
+        self.image.file.is_replayable()
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// An image which is uploaded to edit it or to create variations of it.
+#[derive(Debug)]
+pub struct ImageFile<'a> {
+    pub file: FileSource,
+
+    /// The name of the file, whose extension determines the content type, e.g. `image.png`.
+    pub filename: Cow<'a, str>,
+}
+
+impl<'a> ImageFile<'a> {
+    /// Returns the image file at the path, named after the last component of the path.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let filename = path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self {
+            file: FileSource::Path(path),
+            filename: Cow::Owned(filename),
+        }
+    }
+
+    /// Returns the image in memory.
+    pub fn from_bytes(filename: impl Into<Cow<'a, str>>, content: impl Into<Bytes>) -> Self {
+        Self {
+            file: FileSource::Bytes(content.into()),
+            filename: filename.into(),
+        }
+    }
+}
+
+/// The format in which generated images are returned.
+/// URLs are only valid for an hour after the image has been generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageResponseFormat {
+    Url,
+    B64Json,
+}
+
+impl Display for ImageResponseFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Url => f.write_str("url"),
+            Self::B64Json => f.write_str("b64_json"),
+        }
+    }
+}
+
+/// The file format of generated images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageOutputFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ImageOutputFormat {
+    /// Returns the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::Webp => "webp",
+        }
+    }
+}
+
+impl Display for ImageOutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// The response of an image generation, edit or variation.
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::images::{Image, ImagesResponse};
+///
+/// let response: ImagesResponse = serde_json::from_str(r#"{
+///     "created": 1589478378,
+///     "data": [
+///         { "url": "https://example.com/image.png" },
+///         { "b64_json": "iVBORw0KGgo=", "revised_prompt": "A lighthouse" }
+///     ]
+/// }"#).unwrap();
+///
+/// assert_eq!(response.data[0].image, Image::Url("https://example.com/image.png".to_owned()));
+/// assert_eq!(response.data[1].image, Image::Bytes(b"\x89PNG\r\n\x1a\n".to_vec()));
+/// assert_eq!(response.data[1].revised_prompt.as_deref(), Some("A lighthouse"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ImagesResponse {
+    /// The Unix timestamp (in seconds) of the creation of the images.
+    pub created: u64,
+    pub data: Vec<ImageData>,
+
+    /// The file format of the images, only returned by `gpt-image-1`.
+    #[serde(default)]
+    pub output_format: Option<ImageOutputFormat>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+impl UsageReport for ImagesResponse {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
+
+/// A generated image.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawImageData")]
+pub struct ImageData {
+    pub image: Image,
+
+    /// The prompt that was used to generate the image, if the model revised the prompt.
+    pub revised_prompt: Option<String>,
+}
+
+/// A generated image, depending on the response format either as URL or decoded from base64.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Image {
+    Url(String),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Deserialize)]
+struct RawImageData {
+    url: Option<String>,
+    b64_json: Option<String>,
+    revised_prompt: Option<String>,
+}
+
+impl TryFrom<RawImageData> for ImageData {
+    type Error = String;
+
+    fn try_from(raw: RawImageData) -> Result<Self, Self::Error> {
+        let image = match (raw.b64_json, raw.url) {
+            (Some(encoded), _) => Image::Bytes(STANDARD.decode(encoded).map_err(|error| error.to_string())?),
+            (None, Some(url)) => Image::Url(url),
+            (None, None) => return Err("image has neither url nor b64_json".to_owned())
+        };
+
+        Ok(Self { image, revised_prompt: raw.revised_prompt })
+    }
+}
+
+impl Client {
+    /// Returns the content of the image, downloading it if it was returned as URL.
+    /// The download is sent without the credentials of the client.
+    pub async fn image_bytes(&self, image: &Image) -> Result<Bytes, ResponseError> {
+        let url = match image {
+            Image::Bytes(bytes) => return Ok(Bytes::copy_from_slice(bytes)),
+            Image::Url(url) => url
+        };
+
+        self.retrying(|| async {
+            let request = Request::builder()
+                .method(Method::GET)
+                .uri(url)
+                .body(Body::empty())?;
+            let response = self.dispatch(request).await?;
+            timeout(self.read_timeout, hyper::body::to_bytes(response.into_body())).await?
+                .map_err(ResponseError::from)
+        }).await
+    }
+
+    /// Writes the images of the response to the directory, named `{prefix}-{index}.{extension}`,
+    /// and returns their paths. The extension follows the output format of the response,
+    /// which is PNG unless stated otherwise. The directory is created if it does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use hyper::header::AUTHORIZATION;
+    /// use hyper::StatusCode;
+    /// use openai_rs::endpoints::images::ImagesResponse;
+    /// use openai_rs::openai;
+    /// use openai_rs::transport::{MockResponse, MockTransport};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let transport = MockTransport::new();
+    /// transport.push(MockResponse::new(StatusCode::OK, &b"\x89PNG"[..]));
+    /// let client = openai::builder("api_key").transport(transport.clone()).build().unwrap();
+    ///
+    /// let response: ImagesResponse = serde_json::from_str(r#"{
+    ///     "created": 1589478378,
+    ///     "data": [{ "url": "https://images.example.com/1.png" }, { "b64_json": "iVBORw==" }]
+    /// }"#).unwrap();
+    ///
+    /// let directory = std::env::temp_dir().join("openai-rs-save-images");
+    /// let paths = client.save_images(&response, &directory, "design").await.unwrap();
+    /// assert_eq!(paths[1], directory.join("design-1.png"));
+    /// assert_eq!(std::fs::read(&paths[0]).unwrap(), b"\x89PNG");
+    /// assert_eq!(std::fs::read(&paths[1]).unwrap(), b"\x89PNG");
+    ///
+    /// // Only the URL is downloaded, without the API key.
+    /// let requests = transport.requests();
+    /// assert_eq!(requests.len(), 1);
+    /// assert!(!requests[0].headers.contains_key(AUTHORIZATION));
+    ///
+    /// let response: ImagesResponse = serde_json::from_str(r#"{
+    ///     "created": 1589478378,
+    ///     "data": [{ "b64_json": "UklGRg==" }],
+    ///     "output_format": "webp"
+    /// }"#).unwrap();
+    /// let paths = client.save_images(&response, &directory, "design").await.unwrap();
+    /// assert_eq!(paths[0], directory.join("design-0.webp"));
+    /// # }
+    /// ```
+    pub async fn save_images(
+        &self,
+        response: &ImagesResponse,
+        directory: impl AsRef<Path>,
+        prefix: &str
+    ) -> Result<Vec<PathBuf>, ResponseError> {
+        let directory = directory.as_ref();
+        tokio::fs::create_dir_all(directory).await?;
+
+        let extension = response.output_format.unwrap_or(ImageOutputFormat::Png).extension();
+        let mut paths = Vec::with_capacity(response.data.len());
+        for (index, data) in response.data.iter().enumerate() {
+            let path = directory.join(format!("{}-{}.{}", prefix, index, extension));
+            tokio::fs::write(&path, self.image_bytes(&data.image).await?).await?;
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+}
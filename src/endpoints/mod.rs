@@ -1,68 +1,364 @@
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::io::Error;
+use std::ops::AddAssign;
+use std::time::Duration;
+use hyper::{HeaderMap, StatusCode};
 
 pub mod completion;
 pub mod classification;
 pub mod answer;
+pub mod audio;
+pub mod batch;
 pub mod search;
 pub mod edits;
+pub mod chat;
+pub mod embeddings;
+pub mod files;
+pub mod fine_tuning;
+pub mod images;
+pub mod models;
+pub mod moderations;
+pub mod multipart;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer};
 
 /// This request-Module is for internal purpose
 pub(crate) mod request {
-    use hyper::{Body, Request};
+    use std::borrow::Cow;
+    use std::fmt::Debug;
+    use hyper::body::Bytes;
+    use hyper::header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+    use hyper::{Body, Method, Request};
+    use serde::de::DeserializeOwned;
     use serde::Serialize;
+    use crate::cache::Caching;
+    use super::multipart::Multipart;
+    use super::{ResponseError, UsageReport};
 
-    macro_rules! post {
-        ($endpoint:ident, $auth_token:ident, $serialized:ident) => {{
-            hyper::http::Request::builder()
-                .method(hyper::http::method::Method::POST)
-                .uri($endpoint)
-                .header(hyper::header::AUTHORIZATION, &format!("Bearer {}", $auth_token))
-                .header(hyper::header::CONTENT_TYPE, "application/json")
-                .body(hyper::body::Body::from($serialized)).expect("Failed to build request")
-        }}
+    /// Returns the value of the authorization header of the token.
+    pub(crate) fn bearer(auth_token: &str) -> Result<HeaderValue, ResponseError> {
+        HeaderValue::from_str(&format!("Bearer {}", auth_token))
+            .map_err(|_| ResponseError::InvalidHeader(AUTHORIZATION.to_string()))
+    }
+
+    /// Deserializes a JSON response body.
+    pub(crate) fn json<T: DeserializeOwned>(body: Bytes) -> Result<T, ResponseError> {
+        serde_json::from_slice(&body).map_err(ResponseError::from)
+    }
+
+    /// Percent-encodes a key or value of the query string.
+    pub(crate) fn encode_query(value: &str) -> String {
+        value.bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (byte as char).to_string()
+                },
+                _ => format!("%{:02X}", byte)
+            })
+            .collect()
+    }
+
+    /// Percent-encodes a segment of the path, e.g. an id given by the caller, so that it cannot change
+    /// the target of the request. The colons of fine-tuned model names are kept.
+    pub(crate) fn encode_path_segment(segment: &str) -> String {
+        if segment == "." || segment == ".." {
+            return segment.replace('.', "%2E");
+        }
+
+        segment.bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b':' | b'@' => {
+                    (byte as char).to_string()
+                },
+                _ => format!("%{:02X}", byte)
+            })
+            .collect()
     }
-    pub(super) use post;
 
     /// An Endpoint-Trait which contains the ability to form a request.
     /// This trait is mainly used for internal purpose (implementation of the Endpoint-Trait)
     pub trait Endpoint
     where Self: Serialize {
+        /// The path of the endpoint relative to the api root, e.g. `/engines/{}/completions`.
         const ENDPOINT: &'static str;
 
+        /// The response of the endpoint.
+        type Output: DeserializeOwned + Debug + UsageReport;
+
+        /// Returns the path of the request relative to the api root.
+        /// Endpoints which are scoped to an engine replace the `{}` of `ENDPOINT` with the engine id
+        /// and fail with [`ResponseError::MissingEngine`] if none is given.
+        fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+            Ok(Self::ENDPOINT.to_owned())
+        }
+
+        /// Returns the text which is given to the model as prompt, e.g. to moderate it.
+        fn prompt(&self) -> Vec<Cow<'_, str>> {
+            Vec::new()
+        }
+
+        /// Returns all text the request sends to the model, e.g. to budget it against a rate limit.
+        /// Unlike the prompt, it includes input which is not moderated, like the input of an embedding.
+        fn input_text(&self) -> Vec<Cow<'_, str>> {
+            self.prompt()
+        }
+
+        /// Returns the number of tokens the request sends as token ids instead of text.
+        fn input_tokens(&self) -> u64 {
+            0
+        }
+
+        /// Returns the maximum number of tokens the model may generate for the request,
+        /// e.g. to budget it against a rate limit.
+        fn max_tokens(&self) -> Option<u32> {
+            None
+        }
+
+        /// Returns whether the response of the request may be served from a response cache.
+        /// Endpoints are never cached unless they opt in.
+        fn caching(&self) -> Caching {
+            Caching::Never
+        }
+    }
+
+    /// The body of a request.
+    pub enum RequestBody {
+        Empty,
+        Json(String),
+        Multipart(Multipart),
+    }
+
+    /// An endpoint which describes its HTTP request and parses the body of its response itself.
+    /// Every [`Endpoint`] is sent as JSON `POST` request and answered with a JSON body,
+    /// other endpoints like file uploads or listings implement this trait directly.
+    pub trait HttpEndpoint {
+        /// The response of the endpoint.
+        type Output: Debug + UsageReport;
+
+        /// Returns the method of the request.
+        fn method(&self) -> Method {
+            Method::POST
+        }
+
+        /// Returns the path of the request relative to the api root.
+        fn path(&self, engine_id: Option<&str>) -> Result<String, ResponseError>;
+
+        /// Returns the parameters of the query string, which are percent-encoded when the request is formed.
+        fn query(&self) -> Vec<(&'static str, String)> {
+            Vec::new()
+        }
+
+        /// Returns the body of the request.
+        fn body(&self) -> Result<RequestBody, ResponseError> {
+            Ok(RequestBody::Empty)
+        }
+
+        /// Returns whether the request can be sent again, e.g. to retry it.
+        /// Uploads which stream a reader can only be sent once.
+        fn replayable(&self) -> bool {
+            true
+        }
+
+        /// Returns the text which is given to the model as prompt, e.g. to moderate it.
+        fn prompt(&self) -> Vec<Cow<'_, str>> {
+            Vec::new()
+        }
+
+        /// Returns all text the request sends to the model, e.g. to budget it against a rate limit.
+        fn input_text(&self) -> Vec<Cow<'_, str>> {
+            self.prompt()
+        }
+
+        /// Returns the number of tokens the request sends as token ids instead of text.
+        fn input_tokens(&self) -> u64 {
+            0
+        }
+
+        /// Returns the maximum number of tokens the model may generate for the request,
+        /// e.g. to budget it against a rate limit.
+        fn max_tokens(&self) -> Option<u32> {
+            None
+        }
+
+        /// Returns whether the response of the request may be served from a response cache.
+        /// Endpoints are never cached unless they opt in.
+        fn caching(&self) -> Caching {
+            Caching::Never
+        }
+
+        /// Forms the request by joining the path and the query of the endpoint with the given api url.
         fn request(
             &self,
+            api_url: &str,
             auth_token: &str,
             engine_id: Option<&str>
-        ) -> Request<Body>;
+        ) -> Result<Request<Body>, ResponseError> {
+            let mut endpoint = format!("{}{}", api_url, self.path(engine_id)?);
+            let query = self.query();
+            if !query.is_empty() {
+                let query: Vec<String> = query.iter()
+                    .map(|(key, value)| format!("{}={}", encode_query(key), encode_query(value)))
+                    .collect();
+                endpoint = format!("{}?{}", endpoint, query.join("&"));
+            }
+
+            let method = self.method();
+            let builder = Request::builder()
+                .uri(&endpoint)
+                .header(AUTHORIZATION, bearer(auth_token)?);
+
+            let request = match self.body()? {
+                RequestBody::Empty => {
+                    trace!("method={}, endpoint={}", method, endpoint);
+                    builder.method(method).body(Body::empty())
+                },
+                RequestBody::Json(serialized) => {
+                    trace!("method={}, endpoint={}, serialized={}", method, endpoint, serialized);
+                    builder.method(method)
+                        .header(CONTENT_TYPE, "application/json")
+                        .body(Body::from(serialized))
+                },
+                RequestBody::Multipart(form) => {
+                    trace!("method={}, endpoint={}, multipart", method, endpoint);
+                    builder.method(method)
+                        .header(CONTENT_TYPE, form.content_type())
+                        .body(form.into_body())
+                }
+            };
+
+            request.map_err(ResponseError::from)
+        }
+
+        /// Parses the body of a successful response.
+        fn parse(body: Bytes) -> Result<Self::Output, ResponseError>;
+    }
+
+    impl<T: Endpoint> HttpEndpoint for T {
+        type Output = <T as Endpoint>::Output;
+
+        fn path(&self, engine_id: Option<&str>) -> Result<String, ResponseError> {
+            Endpoint::path(self, engine_id)
+        }
+
+        fn body(&self) -> Result<RequestBody, ResponseError> {
+            serde_json::to_string(self)
+                .map(RequestBody::Json)
+                .map_err(ResponseError::RequestSerialization)
+        }
+
+        fn prompt(&self) -> Vec<Cow<'_, str>> {
+            Endpoint::prompt(self)
+        }
+
+        fn input_text(&self) -> Vec<Cow<'_, str>> {
+            Endpoint::input_text(self)
+        }
+
+        fn input_tokens(&self) -> u64 {
+            Endpoint::input_tokens(self)
+        }
+
+        fn max_tokens(&self) -> Option<u32> {
+            Endpoint::max_tokens(self)
+        }
+
+        fn caching(&self) -> Caching {
+            Endpoint::caching(self)
+        }
+
+        fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+            json(body)
+        }
     }
-}
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct Response {
-    pub id: Option<String>,
-    pub object: Option<String>,
-    pub created: Option<u64>,
-    pub model: Option<String>,
-    pub choices: Option<Vec<Choice>>,
-    pub data: Option<Vec<Data>>,
-    pub completion: Option<String>,
-    pub label: Option<String>,
-    pub search_model: Option<Model>,
-    pub selected_examples: Option<Vec<SelectedExample>>,
-    pub selected_documents: Option<Vec<SelectedDocument>>
+    /// An endpoint which can stream its response as server-sent events.
+    pub trait Streaming
+    where Self: Endpoint {
+        /// A chunk of the streamed response.
+        type Chunk: DeserializeOwned + UsageReport;
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct Choice {
     pub text: String,
     pub index: usize,
-    pub logprobs: Option<u32>,
+    pub logprobs: Option<Logprobs>,
     pub finish_reason: Option<String>
 }
 
+/// The log probabilities of the tokens of a completion, returned if `logprobs` is requested.
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::Logprobs;
+///
+/// let logprobs: Logprobs = serde_json::from_str(r#"{
+///     "tokens": ["Hello", " world"],
+///     "token_logprobs": [null, -0.5],
+///     "top_logprobs": [null, { " world": -0.5, " there": -1.2 }],
+///     "text_offset": [0, 5]
+/// }"#).unwrap();
+///
+/// assert_eq!(logprobs.sequence_log_likelihood(), -0.5);
+/// assert_eq!(logprobs.perplexity(), 0.5f64.exp());
+/// assert_eq!(logprobs.token_probabilities(), vec![None, Some((-0.5f64).exp())]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Logprobs {
+    /// The tokens of the completion (and the prompt if it is echoed).
+    pub tokens: Vec<String>,
+
+    /// The log probability of each token. The first token of an echoed prompt has none.
+    pub token_logprobs: Vec<Option<f64>>,
+
+    /// The most likely alternatives with their log probability at each position.
+    #[serde(default)]
+    pub top_logprobs: Option<Vec<Option<HashMap<String, f64>>>>,
+
+    /// The character offset of each token in the text.
+    #[serde(default)]
+    pub text_offset: Vec<usize>,
+}
+
+impl Logprobs {
+    /// Returns the log-likelihood of the sequence, the sum of the log probabilities of its tokens.
+    /// Tokens without a log probability are skipped.
+    pub fn sequence_log_likelihood(&self) -> f64 {
+        sequence_log_likelihood(self.token_logprobs.iter().flatten().copied())
+    }
+
+    /// Returns the perplexity of the sequence, the exponential of the negative mean log probability.
+    /// Tokens without a log probability are skipped, an empty sequence has a perplexity of 1.
+    pub fn perplexity(&self) -> f64 {
+        perplexity(self.token_logprobs.iter().flatten().copied())
+    }
+
+    /// Returns the probability of each token.
+    pub fn token_probabilities(&self) -> Vec<Option<f64>> {
+        self.token_logprobs.iter()
+            .map(|logprob| logprob.map(f64::exp))
+            .collect()
+    }
+}
+
+/// Returns the sum of the log probabilities.
+pub(crate) fn sequence_log_likelihood(logprobs: impl Iterator<Item = f64>) -> f64 {
+    logprobs.sum()
+}
+
+/// Returns the exponential of the negative mean of the log probabilities.
+pub(crate) fn perplexity(logprobs: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = logprobs
+        .fold((0., 0usize), |(sum, count), logprob| (sum + logprob, count + 1));
+    if count == 0 {
+        1.
+    } else {
+        (-sum / count as f64).exp()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct Data {
     pub document: u32,
@@ -83,27 +379,285 @@ pub struct SelectedDocument {
     pub text: String
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Model {
-    Ada,
-    Babbage,
-    Curie,
-    Davinci
+/// The number of tokens used by a request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct Usage {
+    /// Number of tokens in the prompt.
+    #[serde(default)]
+    pub prompt_tokens: u64,
+
+    /// Number of tokens in the generated completion.
+    #[serde(default)]
+    pub completion_tokens: u64,
+
+    /// Total number of tokens used in the request (prompt + completion).
+    #[serde(default)]
+    pub total_tokens: u64,
+
+    /// Breakdown of the tokens used in the prompt.
+    #[serde(default)]
+    pub prompt_tokens_details: PromptTokensDetails,
+
+    /// Breakdown of the tokens used in the completion.
+    #[serde(default)]
+    pub completion_tokens_details: CompletionTokensDetails,
+}
+
+/// Breakdown of the tokens used in the prompt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct PromptTokensDetails {
+    /// Tokens of the prompt which were served from the prompt cache.
+    #[serde(default)]
+    pub cached_tokens: u64,
+
+    /// Audio input tokens of the prompt.
+    #[serde(default)]
+    pub audio_tokens: u64,
+}
+
+/// Breakdown of the tokens used in the completion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct CompletionTokensDetails {
+    /// Tokens generated by the model for reasoning, which are not part of the output.
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+
+    /// Audio output tokens of the completion.
+    #[serde(default)]
+    pub audio_tokens: u64,
+
+    /// Tokens of a predicted output which appeared in the completion.
+    #[serde(default)]
+    pub accepted_prediction_tokens: u64,
+
+    /// Tokens of a predicted output which did not appear in the completion.
+    /// These are still counted like completion tokens.
+    #[serde(default)]
+    pub rejected_prediction_tokens: u64,
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+        self.prompt_tokens_details.cached_tokens += other.prompt_tokens_details.cached_tokens;
+        self.prompt_tokens_details.audio_tokens += other.prompt_tokens_details.audio_tokens;
+
+        let details = &mut self.completion_tokens_details;
+        let other = other.completion_tokens_details;
+        details.reasoning_tokens += other.reasoning_tokens;
+        details.audio_tokens += other.audio_tokens;
+        details.accepted_prediction_tokens += other.accepted_prediction_tokens;
+        details.rejected_prediction_tokens += other.rejected_prediction_tokens;
+    }
+}
+
+/// A response which reports the tokens used to produce it.
+pub trait UsageReport {
+    /// Returns the usage of the response, if the API reported one.
+    /// Responses without usage, like files or models, keep the default of `None`.
+    fn usage(&self) -> Option<&Usage> {
+        None
+    }
+}
+
+/// Raw content, like a downloaded file, does not report usage.
+impl UsageReport for hyper::body::Bytes {}
+
+/// An error returned by the OpenAI API, including the metadata of the failed response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    /// The status code of the response.
+    pub status: StatusCode,
+
+    /// The error object of the response body.
+    pub error: ErrorObject,
+
+    /// The value of the `x-request-id` header which identifies the request at OpenAI.
+    pub request_id: Option<String>,
+
+    /// The rate limit state reported by the response headers.
+    pub rate_limit: RateLimit,
+
+    /// The delay requested by the `Retry-After` or `retry-after-ms` header.
+    pub retry_after: Option<Duration>,
+}
+
+/// The `error` object of an error response body.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ErrorObject {
+    /// A human-readable description of the error.
+    /// If the body could not be parsed, this contains the raw body instead.
+    pub message: String,
+
+    /// The type of the error, e.g. `invalid_request_error`.
+    #[serde(rename = "type")]
+    pub error_type: Option<String>,
+
+    /// The request parameter which caused the error.
+    pub param: Option<String>,
+
+    /// A machine-readable error code, e.g. `model_not_found`.
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub code: Option<String>,
+}
+
+/// The rate limit state which is reported with every response by the `x-ratelimit-*` headers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// The maximum number of requests permitted before exhausting the rate limit.
+    pub limit_requests: Option<u64>,
+
+    /// The maximum number of tokens permitted before exhausting the rate limit.
+    pub limit_tokens: Option<u64>,
+
+    /// The remaining number of requests permitted before exhausting the rate limit.
+    pub remaining_requests: Option<u64>,
+
+    /// The remaining number of tokens permitted before exhausting the rate limit.
+    pub remaining_tokens: Option<u64>,
+
+    /// The time until the request rate limit resets to its initial state.
+    pub reset_requests: Option<Duration>,
+
+    /// The time until the token rate limit resets to its initial state.
+    pub reset_tokens: Option<Duration>,
+}
+
+impl ApiError {
+    /// Forms the error from the parts of a failed response.
+    pub(crate) fn new(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            error: ErrorObject
+        }
+
+        let error = serde_json::from_slice::<ErrorBody>(body)
+            .map(|body| body.error)
+            .unwrap_or_else(|_| ErrorObject {
+                message: String::from_utf8_lossy(body).into_owned(),
+                ..Default::default()
+            });
+
+        Self {
+            status,
+            error,
+            request_id: header(headers, "x-request-id").map(str::to_owned),
+            rate_limit: RateLimit::from_headers(headers),
+            retry_after: crate::retry::retry_after(headers),
+        }
+    }
+}
+
+impl RateLimit {
+    /// Parses the rate limit state of the `x-ratelimit-*` headers.
+    /// Missing or malformed headers are left as `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use hyper::HeaderMap;
+    /// use openai_rs::endpoints::RateLimit;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("x-ratelimit-remaining-requests", "59".parse().unwrap());
+    /// headers.insert("x-ratelimit-reset-tokens", "6m0.5s".parse().unwrap());
+    ///
+    /// let rate_limit = RateLimit::from_headers(&headers);
+    /// assert_eq!(rate_limit.remaining_requests, Some(59));
+    /// assert_eq!(rate_limit.reset_tokens, Some(Duration::from_millis(360_500)));
+    /// ```
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let number = |name| header(headers, name).and_then(|value| value.parse().ok());
+        let duration = |name| header(headers, name).and_then(parse_duration);
+
+        Self {
+            limit_requests: number("x-ratelimit-limit-requests"),
+            limit_tokens: number("x-ratelimit-limit-tokens"),
+            remaining_requests: number("x-ratelimit-remaining-requests"),
+            remaining_tokens: number("x-ratelimit-remaining-tokens"),
+            reset_requests: duration("x-ratelimit-reset-requests"),
+            reset_tokens: duration("x-ratelimit-reset-tokens"),
+        }
+    }
+}
+
+/// Returns the value of the header if it is present and valid UTF-8.
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Parses a duration in the format of the rate limit headers, e.g. `1s`, `6m0s` or `20ms`.
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let mut total = 0f64;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let split = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+        let (number, tail) = rest.split_at(split);
+        let number: f64 = number.parse().ok()?;
+        let unit_len = tail.find(|c: char| c.is_ascii_digit()).unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+
+        total += number * match unit {
+            "h" => 3600.,
+            "m" => 60.,
+            "s" => 1.,
+            "ms" => 0.001,
+            _ => return None
+        };
+        rest = tail;
+    }
+
+    Some(Duration::from_secs_f64(total))
+}
+
+/// Deserializes an optional value which is either a string or a number into a string.
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where D: Deserializer<'de> {
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(value)) => Some(value),
+        Some(serde_json::Value::Null) | None => None,
+        Some(value) => Some(value.to_string())
+    })
 }
 
 #[derive(Debug)]
 pub enum ResponseError {
     Io(Error),
     Hyper(hyper::Error),
-    ErrorCode(hyper::StatusCode),
+    Api(Box<ApiError>),
     Serialization(serde_json::Error),
-}
+    Http(hyper::http::Error),
+    Tls(openssl::error::ErrorStack),
+    Timeout(std::time::Duration),
+    Transport(Box<dyn std::error::Error + Send + Sync>),
 
-impl Default for Model {
-    fn default() -> Self {
-        Self::Ada
-    }
+    /// The endpoint is scoped to an engine, but no engine id was given.
+    MissingEngine(&'static str),
+
+    /// The name or the value of the named header is malformed.
+    InvalidHeader(String),
+
+    /// The request body could not be serialized.
+    RequestSerialization(serde_json::Error),
+
+    /// The fine-tuning job failed or was cancelled.
+    FineTuningFailed(Box<fine_tuning::FineTuningJob>),
+
+    /// The prompt was flagged by the moderation gate of the client in the named categories,
+    /// so the request was not sent.
+    Flagged(Vec<String>),
+
+    /// The request of a batch failed without a response.
+    BatchRequestFailed(Box<batch::BatchRequestError>),
+
+    /// The batch file has no requests, so it was not submitted.
+    EmptyBatch,
 }
 
 impl Display for ResponseError {
@@ -111,12 +665,49 @@ impl Display for ResponseError {
         match self {
             ResponseError::Io(error) => write!(f, "IO error: {}", error),
             ResponseError::Hyper(error) => write!(f, "Hyper error: {}", error),
-            ResponseError::ErrorCode(status) => write!(f, "Error code: {}", status),
+            ResponseError::Api(error) => write!(f, "API error: {}", error),
             ResponseError::Serialization(error) => write!(f, "Serialization error: {}", error),
+            ResponseError::Http(error) => write!(f, "HTTP error: {}", error),
+            ResponseError::Tls(error) => write!(f, "TLS error: {}", error),
+            ResponseError::Timeout(duration) => write!(f, "Timed out after {:?}", duration),
+            ResponseError::Transport(error) => write!(f, "Transport error: {}", error),
+            ResponseError::MissingEngine(endpoint) => write!(f, "{} requires an engine id", endpoint),
+            ResponseError::InvalidHeader(name) => write!(f, "Invalid value of header {}", name),
+            ResponseError::RequestSerialization(error) => write!(f, "Request serialization error: {}", error),
+            ResponseError::Flagged(categories) => {
+                write!(f, "Prompt flagged by moderation: {}", categories.join(", "))
+            },
+            ResponseError::BatchRequestFailed(error) => match &error.code {
+                Some(code) => write!(f, "Batch request failed ({}): {}", code, error.message),
+                None => write!(f, "Batch request failed: {}", error.message)
+            },
+            ResponseError::EmptyBatch => write!(f, "The batch file has no requests"),
+            ResponseError::FineTuningFailed(job) => {
+                write!(f, "Fine-tuning job {} ended with status {:?}", job.id, job.status)?;
+                match &job.error {
+                    Some(error) => write!(f, ": {}", error.message),
+                    None => Ok(())
+                }
+            },
         }
     }
 }
 
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let Some(error_type) = &self.error.error_type {
+            write!(f, " ({})", error_type)?;
+        }
+        write!(f, ": {}", self.error.message)?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, " [request id: {}]", request_id)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl From<serde_json::Error> for ResponseError {
     fn from(error: serde_json::Error) -> Self {
         Self::Serialization(error)
@@ -135,4 +726,16 @@ impl From<hyper::Error> for ResponseError {
     }
 }
 
+impl From<hyper::http::Error> for ResponseError {
+    fn from(error: hyper::http::Error) -> Self {
+        Self::Http(error)
+    }
+}
+
+impl From<openssl::error::ErrorStack> for ResponseError {
+    fn from(error: openssl::error::ErrorStack) -> Self {
+        Self::Tls(error)
+    }
+}
+
 impl std::error::Error for ResponseError {}
\ No newline at end of file
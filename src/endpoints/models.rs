@@ -0,0 +1,342 @@
+use std::borrow::{Borrow, Cow};
+use std::fmt::{Display, Formatter};
+use hyper::body::Bytes;
+use hyper::Method;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::endpoints::{ResponseError, UsageReport};
+use crate::endpoints::request::{encode_path_segment, json, HttpEndpoint};
+
+/// The identifier of a model, either one of the well-known constants or any other id,
+/// like the name of a fine-tuned model.
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::models::ModelId;
+///
+/// let model = ModelId::new("gpt-4o-2024-08-06");
+/// assert_eq!(model.context_window(), Some(128_000));
+/// assert!(model.info().unwrap().capabilities.vision);
+///
+/// let fine_tuned = ModelId::new("ft:gpt-4o-mini-2024-07-18:my-org:support:7p4lURel");
+/// assert!(fine_tuned.is_fine_tuned());
+/// assert_eq!(fine_tuned.base_model(), ModelId::new("gpt-4o-mini-2024-07-18"));
+/// assert_eq!(fine_tuned.context_window(), ModelId::GPT_4O_MINI.context_window());
+///
+/// assert_eq!(ModelId::new("my-own-model").info(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModelId(Cow<'static, str>);
+
+impl ModelId {
+    pub const ADA: Self = Self::known("ada");
+    pub const BABBAGE: Self = Self::known("babbage");
+    pub const CURIE: Self = Self::known("curie");
+    pub const DAVINCI: Self = Self::known("davinci");
+    pub const BABBAGE_002: Self = Self::known("babbage-002");
+    pub const DAVINCI_002: Self = Self::known("davinci-002");
+    pub const TEXT_DAVINCI_003: Self = Self::known("text-davinci-003");
+    pub const TEXT_DAVINCI_EDIT_001: Self = Self::known("text-davinci-edit-001");
+    pub const GPT_3_5_TURBO: Self = Self::known("gpt-3.5-turbo");
+    pub const GPT_3_5_TURBO_INSTRUCT: Self = Self::known("gpt-3.5-turbo-instruct");
+    pub const GPT_4: Self = Self::known("gpt-4");
+    pub const GPT_4_TURBO: Self = Self::known("gpt-4-turbo");
+    pub const GPT_4O: Self = Self::known("gpt-4o");
+    pub const GPT_4O_MINI: Self = Self::known("gpt-4o-mini");
+    pub const O1: Self = Self::known("o1");
+    pub const O1_MINI: Self = Self::known("o1-mini");
+    pub const O3_MINI: Self = Self::known("o3-mini");
+    pub const TEXT_EMBEDDING_ADA_002: Self = Self::known("text-embedding-ada-002");
+    pub const TEXT_EMBEDDING_3_SMALL: Self = Self::known("text-embedding-3-small");
+    pub const TEXT_EMBEDDING_3_LARGE: Self = Self::known("text-embedding-3-large");
+
+    const fn known(id: &'static str) -> Self {
+        Self(Cow::Borrowed(id))
+    }
+
+    /// Returns the identifier of the model with the given id.
+    pub fn new(id: impl Into<Cow<'static, str>>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns whether the model is a fine-tuned one, whose id starts with `ft:`.
+    pub fn is_fine_tuned(&self) -> bool {
+        self.0.starts_with("ft:")
+    }
+
+    /// Returns the model a fine-tuned model is based on, or the model itself.
+    pub fn base_model(&self) -> ModelId {
+        match self.0.strip_prefix("ft:") {
+            Some(id) => Self::new(id.split(':').next().unwrap_or(id).to_owned()),
+            None => self.clone()
+        }
+    }
+
+    /// Returns the metadata of a well-known model. Dated snapshots like `gpt-4o-2024-08-06`
+    /// and fine-tuned models share the metadata of the model they are based on.
+    pub fn info(&self) -> Option<&'static ModelInfo> {
+        let base = self.base_model();
+        let id = base.as_str();
+
+        KNOWN_MODELS.iter()
+            .filter(|(known, _)| id == *known || id.strip_prefix(known)
+                .is_some_and(|suffix| suffix.starts_with('-') && is_snapshot(&suffix[1..])))
+            .max_by_key(|(known, _)| known.len())
+            .map(|(_, info)| info)
+    }
+
+    /// Returns the maximum number of tokens of the prompt and the completion of a well-known model.
+    pub fn context_window(&self) -> Option<u32> {
+        self.info().map(|info| info.context_window)
+    }
+}
+
+impl Default for ModelId {
+    fn default() -> Self {
+        Self::ADA
+    }
+}
+
+impl Display for ModelId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for ModelId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for ModelId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for ModelId {
+    fn from(id: &str) -> Self {
+        Self::new(id.to_owned())
+    }
+}
+
+impl From<String> for ModelId {
+    fn from(id: String) -> Self {
+        Self::new(id)
+    }
+}
+
+/// Allows a [`ModelId`] as `model` of the endpoints which take the model as string.
+impl<'a> From<ModelId> for Cow<'a, str> {
+    fn from(id: ModelId) -> Self {
+        id.0
+    }
+}
+
+impl Serialize for ModelId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ModelId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// The metadata of a well-known model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// The maximum number of tokens of the prompt and the completion.
+    pub context_window: u32,
+
+    /// The maximum number of tokens of the completion, if it is lower than the context window.
+    pub max_output_tokens: Option<u32>,
+    pub capabilities: Capabilities,
+}
+
+/// The endpoints and features a model supports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Supports the `/completions` endpoint.
+    pub completion: bool,
+
+    /// Supports the `/chat/completions` endpoint.
+    pub chat: bool,
+
+    /// Supports the `/embeddings` endpoint.
+    pub embeddings: bool,
+
+    /// Supports tools and function calling.
+    pub tools: bool,
+
+    /// Supports images as input.
+    pub vision: bool,
+}
+
+const COMPLETION: Capabilities = Capabilities {
+    completion: true, chat: false, embeddings: false, tools: false, vision: false
+};
+const CHAT: Capabilities = Capabilities {
+    completion: false, chat: true, embeddings: false, tools: true, vision: false
+};
+const CHAT_VISION: Capabilities = Capabilities {
+    completion: false, chat: true, embeddings: false, tools: true, vision: true
+};
+const EMBEDDINGS: Capabilities = Capabilities {
+    completion: false, chat: false, embeddings: true, tools: false, vision: false
+};
+
+const fn info(context_window: u32, max_output_tokens: Option<u32>, capabilities: Capabilities) -> ModelInfo {
+    ModelInfo { context_window, max_output_tokens, capabilities }
+}
+
+static KNOWN_MODELS: &[(&str, ModelInfo)] = &[
+    ("ada", info(2_049, None, COMPLETION)),
+    ("babbage", info(2_049, None, COMPLETION)),
+    ("curie", info(2_049, None, COMPLETION)),
+    ("davinci", info(2_049, None, COMPLETION)),
+    ("babbage-002", info(16_384, None, COMPLETION)),
+    ("davinci-002", info(16_384, None, COMPLETION)),
+    ("text-davinci-002", info(4_097, None, COMPLETION)),
+    ("text-davinci-003", info(4_097, None, COMPLETION)),
+    ("text-davinci-edit-001", info(2_049, None, COMPLETION)),
+    ("gpt-3.5-turbo", info(16_385, Some(4_096), CHAT)),
+    ("gpt-3.5-turbo-instruct", info(4_096, None, COMPLETION)),
+    ("gpt-4", info(8_192, None, CHAT)),
+    ("gpt-4-32k", info(32_768, None, CHAT)),
+    ("gpt-4-turbo", info(128_000, Some(4_096), CHAT_VISION)),
+    ("gpt-4o", info(128_000, Some(16_384), CHAT_VISION)),
+    ("gpt-4o-mini", info(128_000, Some(16_384), CHAT_VISION)),
+    ("o1", info(200_000, Some(100_000), CHAT_VISION)),
+    ("o1-mini", info(128_000, Some(65_536), Capabilities { tools: false, ..CHAT })),
+    ("o3-mini", info(200_000, Some(100_000), CHAT)),
+    ("text-embedding-ada-002", info(8_191, None, EMBEDDINGS)),
+    ("text-embedding-3-small", info(8_191, None, EMBEDDINGS)),
+    ("text-embedding-3-large", info(8_191, None, EMBEDDINGS)),
+];
+
+/// Returns whether the suffix of a model id denotes a snapshot, like `2024-08-06`, `0613` or `preview`.
+fn is_snapshot(suffix: &str) -> bool {
+    suffix == "preview"
+        || suffix.split('-').all(|part| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+/// Lists the models which are available, including the fine-tuned models of the organization.
+///
+/// # Example
+/// ```
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::models::ListModels;
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let models = client.create(None, &ListModels).await.unwrap();
+/// for model in models.data.iter().filter(|model| model.id.is_fine_tuned()) {
+///     println!("{} (based on {})", model.id, model.id.base_model());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ListModels;
+
+impl HttpEndpoint for ListModels {
+    type Output = ModelList;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok("/models".to_owned())
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Returns a model.
+#[derive(Debug, Default, Clone)]
+pub struct RetrieveModel {
+    pub model: ModelId,
+}
+
+impl HttpEndpoint for RetrieveModel {
+    type Output = ModelObject;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/models/{}", encode_path_segment(self.model.as_str())))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// Deletes a fine-tuned model of the organization.
+#[derive(Debug, Default, Clone)]
+pub struct DeleteModel {
+    pub model: ModelId,
+}
+
+impl HttpEndpoint for DeleteModel {
+    type Output = DeletedModel;
+
+    fn method(&self) -> Method {
+        Method::DELETE
+    }
+
+    fn path(&self, _engine_id: Option<&str>) -> Result<String, ResponseError> {
+        Ok(format!("/models/{}", encode_path_segment(self.model.as_str())))
+    }
+
+    fn parse(body: Bytes) -> Result<Self::Output, ResponseError> {
+        json(body)
+    }
+}
+
+/// A model as listed by the API.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModelObject {
+    pub id: ModelId,
+    pub object: String,
+
+    /// The Unix timestamp (in seconds) of the creation of the model.
+    pub created: u64,
+
+    /// The organization which owns the model.
+    pub owned_by: String,
+}
+
+impl UsageReport for ModelObject {}
+
+/// The response of [`ListModels`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModelList {
+    pub object: String,
+    pub data: Vec<ModelObject>,
+}
+
+impl UsageReport for ModelList {}
+
+/// The response of [`DeleteModel`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeletedModel {
+    pub id: ModelId,
+    pub object: String,
+    pub deleted: bool,
+}
+
+impl UsageReport for DeletedModel {}
@@ -0,0 +1,254 @@
+use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
+use crate::endpoints::UsageReport;
+use crate::endpoints::request::Endpoint;
+
+/// Classifies if text is potentially harmful.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use openai_rs::client::Client;
+/// use openai_rs::endpoints::moderations::{Moderation, ModerationInput};
+/// use openai_rs::openai;
+///
+/// # async fn example() {
+/// let client: Client = openai::new("api_key");
+///
+/// let moderation = Moderation {
+///     input: ModerationInput::Text(Cow::Borrowed("I want to hurt them.")),
+///     ..Default::default()
+/// };
+///
+/// let response = client.create(None, &moderation).await.unwrap();
+/// if response.results[0].flagged {
+///     println!("flagged for {:?}", response.results[0].flagged_categories());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Moderation<'a> {
+    /// The input text to classify.
+    pub input: ModerationInput<'a>,
+
+    /// The moderation model to use, e.g. `omni-moderation-latest` or `text-moderation-stable`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<Cow<'a, str>>,
+}
+
+impl Default for Moderation<'_> {
+    fn default() -> Self {
+        Self {
+            input: ModerationInput::Text(Cow::Borrowed("")),
+            model: None
+        }
+    }
+}
+
+impl Endpoint for Moderation<'_> {
+    const ENDPOINT: &'static str = "/moderations";
+
+    type Output = ModerationResponse;
+
+    fn input_text(&self) -> Vec<Cow<'_, str>> {
+        match &self.input {
+            ModerationInput::Text(text) => vec![Cow::Borrowed(&**text)],
+            ModerationInput::Texts(texts) => texts.iter().map(|text| Cow::Borrowed(&**text)).collect(),
+        }
+    }
+}
+
+/// The input of a [`Moderation`], either a single or a batch of strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum ModerationInput<'a> {
+    Text(Cow<'a, str>),
+    Texts(Vec<Cow<'a, str>>),
+}
+
+/// The response of a [`Moderation`], with one result per input.
+///
+/// # Example
+/// ```
+/// use openai_rs::endpoints::moderations::ModerationResponse;
+///
+/// let response: ModerationResponse = serde_json::from_str(r#"{
+///     "id": "modr-XXXXX",
+///     "model": "omni-moderation-latest",
+///     "results": [{
+///         "flagged": true,
+///         "categories": { "harassment": true, "violence": true, "self-harm": false },
+///         "category_scores": { "harassment": 0.82, "violence": 0.97, "self-harm": 0.0001 }
+///     }]
+/// }"#).unwrap();
+///
+/// assert_eq!(response.results[0].flagged_categories(), ["harassment", "violence"]);
+/// assert_eq!(response.results[0].category_scores.violence, 0.97);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ModerationResponse {
+    pub id: String,
+    pub model: String,
+    pub results: Vec<ModerationResult>,
+}
+
+impl UsageReport for ModerationResponse {}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ModerationResult {
+    /// Whether the input violates any of the categories.
+    pub flagged: bool,
+
+    /// Whether the input violates each category.
+    pub categories: Categories<bool>,
+
+    /// The confidence of the model per category, between 0 and 1.
+    pub category_scores: Categories<f64>,
+}
+
+impl ModerationResult {
+    /// Returns the names of the categories the input violates.
+    pub fn flagged_categories(&self) -> Vec<&'static str> {
+        self.categories.named()
+            .into_iter()
+            .filter(|(_, flagged)| *flagged)
+            .map(|(name, _)| name)
+            .collect()
+    }
+}
+
+/// A value per moderation category. Categories which are not returned by the model are defaulted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Categories<T> {
+    pub hate: T,
+    #[serde(rename = "hate/threatening")]
+    pub hate_threatening: T,
+    pub harassment: T,
+    #[serde(rename = "harassment/threatening")]
+    pub harassment_threatening: T,
+    pub illicit: T,
+    #[serde(rename = "illicit/violent")]
+    pub illicit_violent: T,
+    #[serde(rename = "self-harm")]
+    pub self_harm: T,
+    #[serde(rename = "self-harm/intent")]
+    pub self_harm_intent: T,
+    #[serde(rename = "self-harm/instructions")]
+    pub self_harm_instructions: T,
+    pub sexual: T,
+    #[serde(rename = "sexual/minors")]
+    pub sexual_minors: T,
+    pub violence: T,
+    #[serde(rename = "violence/graphic")]
+    pub violence_graphic: T,
+}
+
+impl<T: Copy> Categories<T> {
+    /// Returns the value of each category, named as by the API.
+    pub fn named(&self) -> [(&'static str, T); 13] {
+        [
+            ("hate", self.hate),
+            ("hate/threatening", self.hate_threatening),
+            ("harassment", self.harassment),
+            ("harassment/threatening", self.harassment_threatening),
+            ("illicit", self.illicit),
+            ("illicit/violent", self.illicit_violent),
+            ("self-harm", self.self_harm),
+            ("self-harm/intent", self.self_harm_intent),
+            ("self-harm/instructions", self.self_harm_instructions),
+            ("sexual", self.sexual),
+            ("sexual/minors", self.sexual_minors),
+            ("violence", self.violence),
+            ("violence/graphic", self.violence_graphic),
+        ]
+    }
+}
+
+/// A gate which moderates the prompt of each request before it is sent,
+/// see [`ClientBuilder::moderation_gate`](crate::client::ClientBuilder::moderation_gate).
+/// A request whose prompt is flagged fails with [`ResponseError::Flagged`] instead of being sent.
+///
+/// [`ResponseError::Flagged`]: crate::endpoints::ResponseError::Flagged
+///
+/// # Example
+/// ```
+/// use hyper::StatusCode;
+/// use openai_rs::endpoints::chat::{ChatCompletion, ChatMessage};
+/// use openai_rs::endpoints::moderations::ModerationGate;
+/// use openai_rs::endpoints::ResponseError;
+/// use openai_rs::openai;
+/// use openai_rs::transport::{MockResponse, MockTransport};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let transport = MockTransport::new();
+/// transport.push(MockResponse::json(StatusCode::OK, &serde_json::json!({
+///     "id": "modr-XXXXX",
+///     "model": "omni-moderation-latest",
+///     "results": [{
+///         "flagged": true,
+///         "categories": { "violence": true },
+///         "category_scores": { "violence": 0.97 }
+///     }]
+/// })));
+///
+/// let client = openai::builder("api_key")
+///     .transport(transport.clone())
+///     .moderation_gate(ModerationGate::default())
+///     .build()
+///     .unwrap();
+///
+/// let chat = ChatCompletion {
+///     messages: vec![ChatMessage::user("I want to hurt them.")],
+///     ..Default::default()
+/// };
+/// let response = client.create(None, &chat).await;
+///
+/// assert!(matches!(response, Err(ResponseError::Flagged(categories)) if categories == ["violence"]));
+/// assert_eq!(transport.requests().len(), 1);
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModerationGate {
+    /// The moderation model to use, the default model of the API if `None`.
+    pub model: Option<String>,
+
+    /// The categories which block a request. If empty, a request is blocked if its prompt is flagged at all.
+    pub categories: Vec<String>,
+}
+
+impl ModerationGate {
+    /// Sets the moderation model to use.
+    pub fn model(mut self, model: &str) -> Self {
+        self.model = Some(model.to_owned());
+        self
+    }
+
+    /// Sets the categories which block a request.
+    pub fn categories(mut self, categories: &[&str]) -> Self {
+        self.categories = categories.iter().map(|category| category.to_string()).collect();
+        self
+    }
+
+    /// Returns the categories of the results which block a request.
+    pub(crate) fn blocked(&self, response: &ModerationResponse) -> Vec<String> {
+        let mut blocked: Vec<String> = Vec::new();
+        for result in response.results.iter().filter(|result| result.flagged) {
+            let categories = result.flagged_categories();
+            // A result may be flagged without a category the crate knows.
+            if categories.is_empty() && self.categories.is_empty() && blocked.is_empty() {
+                blocked.push("flagged".to_owned());
+            }
+
+            for category in categories {
+                let gated = self.categories.is_empty() || self.categories.iter().any(|gated| gated == category);
+                if gated && !blocked.iter().any(|known| known == category) {
+                    blocked.push(category.to_owned());
+                }
+            }
+        }
+
+        blocked
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Mutex;
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+use hyper::body::Bytes;
+use hyper::Body;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use crate::endpoints::ResponseError;
+
+/// The size of the chunks in which the content of a file is streamed.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+type ByteStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>;
+
+/// The content of an uploaded file.
+pub enum FileSource {
+    /// A file on disk, which is opened when the request is sent and streamed.
+    Path(PathBuf),
+
+    /// Content in memory.
+    Bytes(Bytes),
+
+    /// An async reader, which is streamed and can only be sent once, so its requests are not retried.
+    Reader(Mutex<Option<Box<dyn AsyncRead + Send + Unpin>>>),
+}
+
+impl FileSource {
+    /// Returns whether the content can be sent again, which is the case unless it is a reader.
+    pub fn is_replayable(&self) -> bool {
+        !matches!(self, Self::Reader(_))
+    }
+
+    /// Returns a source streaming the content of the reader.
+    pub fn reader<R>(reader: R) -> Self
+        where R: AsyncRead + Send + Unpin + 'static {
+        Self::Reader(Mutex::new(Some(Box::new(reader))))
+    }
+}
+
+impl From<PathBuf> for FileSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&Path> for FileSource {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_owned())
+    }
+}
+
+impl From<Bytes> for FileSource {
+    fn from(content: Bytes) -> Self {
+        Self::Bytes(content)
+    }
+}
+
+impl From<Vec<u8>> for FileSource {
+    fn from(content: Vec<u8>) -> Self {
+        Self::Bytes(content.into())
+    }
+}
+
+impl Debug for FileSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Self::Bytes(content) => write!(f, "Bytes({} bytes)", content.len()),
+            Self::Reader(_) => f.write_str("Reader"),
+        }
+    }
+}
+
+/// A `multipart/form-data` body. Parts in memory are concatenated,
+/// the content of files and readers is streamed.
+pub struct Multipart {
+    boundary: String,
+    segments: Vec<Segment>,
+}
+
+enum Segment {
+    Bytes(Bytes),
+    Stream(ByteStream),
+}
+
+impl Multipart {
+    /// Returns an empty form with a random boundary.
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("openai-rs-{:016x}", rand::random::<u64>()),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Appends a text field.
+    pub fn text(mut self, name: &str, value: impl Display) -> Self {
+        let part = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            self.boundary, escape(name), value
+        );
+        self.segments.push(Segment::Bytes(Bytes::from(part)));
+        self
+    }
+
+    /// Appends a text field if the value is present.
+    pub fn optional(self, name: &str, value: Option<impl Display>) -> Self {
+        match value {
+            Some(value) => self.text(name, value),
+            None => self
+        }
+    }
+
+    /// Appends a file. The content type is derived from the extension of the filename.
+    /// Fails if the reader was already consumed, a file which cannot be opened fails the request
+    /// when its body is sent.
+    pub fn file(mut self, name: &str, filename: &str, source: &FileSource) -> Result<Self, ResponseError> {
+        let head = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            self.boundary, escape(name), escape(filename), content_type(filename)
+        );
+        self.segments.push(Segment::Bytes(Bytes::from(head)));
+
+        match source {
+            FileSource::Bytes(content) => self.segments.push(Segment::Bytes(content.clone())),
+            FileSource::Path(path) => {
+                // The file is opened once the body is streamed, so building the body never blocks.
+                let file = stream::once(tokio::fs::File::open(path.clone()));
+                self.segments.push(Segment::Stream(Box::pin(file.map_ok(read_chunks).try_flatten())));
+            },
+            FileSource::Reader(reader) => {
+                let reader = reader.lock().unwrap().take().ok_or_else(|| {
+                    Error::other("the reader of the upload was already consumed")
+                })?;
+                self.segments.push(Segment::Stream(Box::pin(read_chunks(reader))));
+            }
+        }
+
+        self.segments.push(Segment::Bytes(Bytes::from_static(b"\r\n")));
+        Ok(self)
+    }
+
+    /// Returns the value of the `Content-Type` header of the form.
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// Closes the form and returns it as body.
+    pub fn into_body(mut self) -> Body {
+        let tail = format!("--{}--\r\n", self.boundary);
+        self.segments.push(Segment::Bytes(Bytes::from(tail)));
+
+        if self.segments.iter().all(|segment| matches!(segment, Segment::Bytes(_))) {
+            let content: Vec<u8> = self.segments.into_iter()
+                .flat_map(|segment| match segment {
+                    Segment::Bytes(bytes) => bytes.to_vec(),
+                    Segment::Stream(_) => Vec::new()
+                })
+                .collect();
+            return Body::from(content);
+        }
+
+        let segments = stream::iter(self.segments).flat_map(|segment| -> ByteStream {
+            match segment {
+                Segment::Bytes(bytes) => Box::pin(stream::once(async move { Ok(bytes) })),
+                Segment::Stream(stream) => stream
+            }
+        });
+        Body::wrap_stream(segments)
+    }
+}
+
+impl Default for Multipart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the reader in chunks until it is exhausted or fails.
+fn read_chunks<R>(reader: R) -> impl Stream<Item = std::io::Result<Bytes>>
+    where R: AsyncRead + Unpin {
+    stream::unfold(Some(reader), |reader| async move {
+        let mut reader = reader?;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        match reader.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(read) => {
+                buffer.truncate(read);
+                Some((Ok(Bytes::from(buffer)), Some(reader)))
+            },
+            Err(error) => Some((Err(error), None))
+        }
+    })
+}
+
+/// Escapes the quotes and line breaks of a name in a `Content-Disposition` header.
+fn escape(name: &str) -> String {
+    name.replace('"', "%22").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Returns the media type of a file, derived from the extension of its name.
+fn content_type(filename: &str) -> &'static str {
+    let extension = filename.rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "mp3" | "mpga" | "mpeg" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "m4a" => "audio/mp4",
+        "wav" => "audio/wav",
+        "webm" => "audio/webm",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "json" => "application/json",
+        "jsonl" => "application/jsonl",
+        "txt" => "text/plain",
+        _ => "application/octet-stream"
+    }
+}
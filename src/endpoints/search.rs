@@ -1,6 +1,7 @@
 use std::borrow::Cow;
-use hyper::{Body, Request};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use crate::cache::Caching;
+use crate::endpoints::{Data, ResponseError, Usage, UsageReport};
 use crate::endpoints::request::Endpoint;
 
 /// Given a query and a set of documents or labels, the model ranks each document based
@@ -46,14 +47,38 @@ impl Default for Search<'_> {
 }
 
 impl Endpoint for Search<'_> {
-    const ENDPOINT: &'static str = "https://api.openai.com/v1/engines/{}/search";
+    const ENDPOINT: &'static str = "/engines/{}/search";
 
-    fn request(&self, auth_token: &str, engine_id: Option<&str>) -> Request<Body> {
-        let endpoint = Self::ENDPOINT.replace("{}", engine_id.unwrap());
-        let serialized = serde_json::to_string(&self)
-            .expect("Failed to serialize Search");
-        trace!("endpoint={}, serialized={}", endpoint, serialized);
+    type Output = SearchResponse;
 
-        super::request::post!(endpoint, auth_token, serialized)
+    fn path(&self, engine_id: Option<&str>) -> Result<String, ResponseError> {
+        let engine_id = engine_id.ok_or(ResponseError::MissingEngine(Self::ENDPOINT))?;
+        Ok(Self::ENDPOINT.replace("{}", engine_id))
     }
-}
\ No newline at end of file
+
+    fn input_text(&self) -> Vec<Cow<'_, str>> {
+        std::iter::once(&self.query)
+            .chain(&self.documents)
+            .map(|text| Cow::Borrowed(&**text))
+            .collect()
+    }
+
+    fn caching(&self) -> Caching {
+        Caching::Deterministic
+    }
+}
+
+/// The response of a [`Search`], with a score for each document.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SearchResponse {
+    pub object: String,
+    pub data: Vec<Data>,
+    pub model: Option<String>,
+    pub usage: Option<Usage>,
+}
+
+impl UsageReport for SearchResponse {
+    fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+}
@@ -7,8 +7,8 @@
 /// ```rust
 /// use std::borrow::Cow;
 /// use openai_rs::client::Client;
-/// use openai_rs::endpoints::edits::Edit;
-/// use openai_rs::endpoints::{Response, ResponseError};
+/// use openai_rs::endpoints::edits::{Edit, EditResponse};
+/// use openai_rs::endpoints::ResponseError;
 /// use openai_rs::openai;
 ///
 /// #[tokio::main]
@@ -24,7 +24,7 @@
 ///     };
 ///
 ///     // Send the request to the OpenAI API.
-///     let response: Result<Response, ResponseError> = client.create(
+///     let response: Result<EditResponse, ResponseError> = client.create(
 ///         Some("text-davinci-edit-001"), &edit
 ///     ).await;
 /// }
@@ -34,10 +34,17 @@
 /// * An api key at [OpenAI API](https://openai.com/api-docs/) for the Client.
 /// * An async runtime like [tokio](https://tokio.rs) in order to use the async functions.
 ///
-
 pub mod openai;
+pub mod cache;
 pub mod client;
 pub mod endpoints;
+pub mod rate_limit;
+pub mod retry;
+pub mod stream;
+#[cfg(feature = "tokenizer")]
+pub mod tokenizer;
+pub mod transport;
+pub mod usage;
 
 #[macro_use]
 extern crate log;
@@ -1,5 +1,10 @@
-use hyper_openssl::HttpsConnector;
-use crate::client::Client;
+use crate::client::{Client, ClientBuilder};
+
+/// The base url of the public OpenAI API.
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com";
+
+/// The path prefix of the current OpenAI API version.
+pub const DEFAULT_API_ROOT: &str = "/v1";
 
 /// Returns a new client for the OpenAI API.
 ///
@@ -14,14 +19,29 @@ use crate::client::Client;
 /// // Create the Client with your API key.
 /// let client: Client = openai::new("api_key");
 /// ```
+///
+/// # Panics
+/// Panics if the HTTPS connector cannot be created, use [`builder`] to handle this case.
 pub fn new(api_key: &str) -> Client {
-    let hyper_client = hyper::Client::builder()
-        .http2_only(true)
-        .pool_idle_timeout(std::time::Duration::from_secs(10))
-        .build(HttpsConnector::new().expect("Could not create HTTPS connector"));
+    builder(api_key).build().expect("Could not create HTTPS connector")
+}
 
-    Client {
-        api_key: api_key.to_owned(),
-        https: hyper_client,
-    }
-}
\ No newline at end of file
+/// Returns a new builder for a client of the OpenAI API.
+///
+/// # Arguments
+/// * `api_key` - The API key to use.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use openai_rs::client::Client;
+/// use openai_rs::openai;
+///
+/// let client: Client = openai::builder("api_key")
+///     .request_timeout(Duration::from_secs(30))
+///     .build()
+///     .expect("Could not build client");
+/// ```
+pub fn builder(api_key: &str) -> ClientBuilder {
+    ClientBuilder::new(api_key)
+}
@@ -0,0 +1,287 @@
+//! Client-side rate limiting of requests and tokens per minute.
+//!
+//! A [`RateLimiter`] is configured on the [`Client`](crate::client::Client) and admits each
+//! request only when both its requests-per-minute and its tokens-per-minute bucket have enough budget
+//! left. Callers which would exceed the budget are queued in order instead of failing. The tokens of a
+//! request are estimated from all of its input, e.g. the texts of an embedding, and its `max_tokens`
+//! before it is sent.
+//!
+//! Both buckets refill continuously over a minute and adapt to the `x-ratelimit-*` headers of
+//! every response: the limits reported by the API cap the configured ones, the remaining budget
+//! of the organization caps the budget of the bucket, and an exhausted limit pauses the bucket until
+//! it resets. A bucket which is not configured is adopted from the headers.
+//!
+//! # Example
+//! ```
+//! use openai_rs::client::Client;
+//! use openai_rs::openai;
+//! use openai_rs::rate_limit::RateLimiter;
+//!
+//! let client: Client = openai::builder("api_key")
+//!     .rate_limiter(RateLimiter::new()
+//!         .requests_per_minute(500)
+//!         .tokens_per_minute(90_000))
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
+//! Embeddings only consume the tokens of their input, which count against the budget as well.
+//! ```
+//! use std::borrow::Cow;
+//! use std::time::Duration;
+//! use hyper::StatusCode;
+//! use openai_rs::endpoints::embeddings::{Embedding, EmbeddingInput};
+//! use openai_rs::openai;
+//! use openai_rs::rate_limit::RateLimiter;
+//! use openai_rs::transport::{MockResponse, MockTransport};
+//! use tokio::time::Instant;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let response = serde_json::json!({
+//!     "object": "list", "data": [], "model": "text-embedding-3-small",
+//!     "usage": { "prompt_tokens": 0, "total_tokens": 0 }
+//! });
+//! let transport = MockTransport::new();
+//! transport.push(MockResponse::json(StatusCode::OK, &response))
+//!     .push(MockResponse::json(StatusCode::OK, &response));
+//!
+//! // A budget of 100 tokens per second.
+//! let client = openai::builder("api_key")
+//!     .transport(transport)
+//!     .rate_limiter(RateLimiter::new().tokens_per_minute(6_000))
+//!     .build()
+//!     .unwrap();
+//! let embedding = |text: String| Embedding {
+//!     model: Cow::Borrowed("text-embedding-3-small"),
+//!     input: EmbeddingInput::Text(Cow::Owned(text)),
+//!     ..Default::default()
+//! };
+//!
+//! let start = Instant::now();
+//! // An estimated token per four bytes exhausts the budget.
+//! client.create(None, &embedding("word".repeat(6_000))).await.unwrap();
+//! // The next 10 tokens are available after 100ms.
+//! client.create(None, &embedding("word".repeat(10))).await.unwrap();
+//! assert!(start.elapsed() >= Duration::from_millis(100));
+//! # }
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use crate::endpoints::RateLimit;
+
+/// A limiter of the requests and tokens per minute which are sent by a client
+/// and all clients derived from it.
+pub struct RateLimiter {
+    requests: Mutex<Bucket>,
+    tokens: Mutex<Bucket>,
+    estimator: fn(&str) -> u64,
+
+    /// Serves the waiting callers in the order they arrived.
+    queue: tokio::sync::Mutex<()>,
+}
+
+impl RateLimiter {
+    /// Returns a limiter without limits, which adopts the limits reported by the API.
+    pub fn new() -> Self {
+        Self {
+            requests: Mutex::new(Bucket::new(None)),
+            tokens: Mutex::new(Bucket::new(None)),
+            estimator: estimate_tokens,
+            queue: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Sets the number of requests which may be sent per minute.
+    pub fn requests_per_minute(self, limit: u64) -> Self {
+        *self.requests.lock().unwrap() = Bucket::new(Some(limit));
+        self
+    }
+
+    /// Sets the number of tokens which may be sent per minute.
+    pub fn tokens_per_minute(self, limit: u64) -> Self {
+        *self.tokens.lock().unwrap() = Bucket::new(Some(limit));
+        self
+    }
+
+    /// Sets the function which estimates the tokens of a prompt. By default, a token is
+    /// estimated per four bytes of text, which overestimates non-English text rather than underestimating it.
+    /// Prompts can be counted exactly with `Tokenizer::count` of the `tokenizer` feature.
+    pub fn estimator(mut self, estimator: fn(&str) -> u64) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    /// Returns the estimated tokens of a request with the input which may generate up to `max_tokens`.
+    pub fn estimate<S: AsRef<str>>(&self, input: &[S], max_tokens: Option<u32>) -> u64 {
+        input.iter().map(|text| (self.estimator)(text.as_ref())).sum::<u64>()
+            + max_tokens.unwrap_or_default() as u64
+    }
+
+    /// Waits until a request with the given tokens fits into the budget and takes it from the budget.
+    /// A request with more tokens than the limit waits until the whole budget is available.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use hyper::HeaderMap;
+    /// use openai_rs::endpoints::RateLimit;
+    /// use openai_rs::rate_limit::RateLimiter;
+    /// use tokio::time::Instant;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let limiter = RateLimiter::new().requests_per_minute(3_000);
+    /// limiter.acquire(100).await;
+    ///
+    /// // The API reports that the organization has exhausted its requests for the next 200ms.
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("x-ratelimit-remaining-requests", "0".parse().unwrap());
+    /// headers.insert("x-ratelimit-reset-requests", "200ms".parse().unwrap());
+    /// limiter.update(&RateLimit::from_headers(&headers));
+    ///
+    /// let start = Instant::now();
+    /// limiter.acquire(100).await;
+    /// assert!(start.elapsed() >= Duration::from_millis(200));
+    /// # }
+    /// ```
+    pub async fn acquire(&self, tokens: u64) {
+        let _turn = self.queue.lock().await;
+        loop {
+            let wait = {
+                let mut requests = self.requests.lock().unwrap();
+                let mut tokens_bucket = self.tokens.lock().unwrap();
+                let now = Instant::now();
+
+                let wait = requests.wait(1, now).max(tokens_bucket.wait(tokens, now));
+                if wait.is_zero() {
+                    requests.take(1);
+                    tokens_bucket.take(tokens);
+                    return;
+                }
+                wait
+            };
+
+            debug!("Rate limited, waiting {:?} for {} tokens", wait, tokens);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Adapts the budget to the rate limit state reported by a response.
+    pub fn update(&self, rate_limit: &RateLimit) {
+        let now = Instant::now();
+        self.requests.lock().unwrap().update(
+            rate_limit.limit_requests, rate_limit.remaining_requests, rate_limit.reset_requests, now
+        );
+        self.tokens.lock().unwrap().update(
+            rate_limit.limit_tokens, rate_limit.remaining_tokens, rate_limit.reset_tokens, now
+        );
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for RateLimiter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("requests", &*self.requests.lock().unwrap())
+            .field("tokens", &*self.tokens.lock().unwrap())
+            .finish()
+    }
+}
+
+/// Estimates a token per four bytes of the text.
+fn estimate_tokens(text: &str) -> u64 {
+    (text.len() as u64).div_ceil(4)
+}
+
+/// A token bucket which refills its capacity once per minute.
+#[derive(Debug)]
+struct Bucket {
+    /// The limit which was configured, `None` if it is adopted from the API.
+    limit: Option<u64>,
+
+    /// The effective limit, `None` if the bucket is unlimited.
+    capacity: Option<u64>,
+    available: f64,
+    refilled: Instant,
+
+    /// The instant until which the limit of the API is exhausted.
+    paused_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(limit: Option<u64>) -> Self {
+        Self {
+            limit,
+            capacity: limit,
+            available: limit.unwrap_or_default() as f64,
+            refilled: Instant::now(),
+            paused_until: None,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if let Some(capacity) = self.capacity {
+            let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+            self.available = (self.available + elapsed * capacity as f64 / 60.).min(capacity as f64);
+        }
+        self.refilled = now;
+    }
+
+    /// Returns how long to wait until the amount is available, zero if it is.
+    fn wait(&mut self, amount: u64, now: Instant) -> Duration {
+        self.refill(now);
+        if let Some(paused_until) = self.paused_until {
+            if paused_until > now {
+                return paused_until - now;
+            }
+            self.paused_until = None;
+        }
+
+        let capacity = match self.capacity {
+            Some(capacity) if capacity > 0 => capacity,
+            _ => return Duration::ZERO
+        };
+
+        let missing = amount.min(capacity) as f64 - self.available;
+        if missing <= 0. {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing * 60. / capacity as f64)
+        }
+    }
+
+    fn take(&mut self, amount: u64) {
+        if self.capacity.is_some() {
+            self.available -= amount as f64;
+        }
+    }
+
+    fn update(&mut self, limit: Option<u64>, remaining: Option<u64>, reset: Option<Duration>, now: Instant) {
+        self.refill(now);
+        if let Some(limit) = limit.filter(|limit| *limit > 0) {
+            let capacity = self.limit.map_or(limit, |configured| configured.min(limit));
+            if self.capacity.is_none() {
+                // An adopted bucket starts with the budget the API reports as remaining.
+                self.available = remaining.unwrap_or(capacity).min(capacity) as f64;
+            }
+            self.capacity = Some(capacity);
+        }
+
+        if let (Some(_), Some(remaining)) = (self.capacity, remaining) {
+            self.available = self.available.min(remaining as f64);
+        }
+
+        if let (Some(0), Some(reset)) = (remaining, reset) {
+            self.paused_until = Some(now + reset);
+        }
+    }
+}
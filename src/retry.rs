@@ -0,0 +1,247 @@
+//! Retrying of failed requests with exponential backoff.
+//!
+//! A [`RetryPolicy`] is configured on the [`Client`](crate::client::Client) and decides
+//! after each failed attempt whether and how long to wait before sending the request again.
+//! Delays requested by the API through `Retry-After` or `x-ratelimit-reset-*` headers
+//! take precedence over the computed backoff.
+//!
+//! # Example
+//! The policy can be tested against a local stub server which answers with a scripted `429`:
+//! ```
+//! use std::borrow::Cow;
+//! use std::time::Duration;
+//! use openai_rs::endpoints::edits::{Edit, EditResponse};
+//! use openai_rs::openai;
+//! use openai_rs::retry::RetryPolicy;
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//! use tokio::net::TcpListener;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+//! let base_url = format!("http://{}", listener.local_addr().unwrap());
+//!
+//! tokio::spawn(async move {
+//!     let responses = [
+//!         "HTTP/1.1 429 Too Many Requests\r\nretry-after-ms: 10\r\ncontent-length: 2\r\n\r\n{}",
+//!         "HTTP/1.1 200 OK\r\ncontent-length: 42\r\n\r\n{\"object\":\"edit\",\"created\":0,\"choices\":[]}",
+//!     ];
+//!
+//!     for response in responses {
+//!         let (mut socket, _) = listener.accept().await.unwrap();
+//!         # let mut request = Vec::new();
+//!         # let mut buffer = [0u8; 1024];
+//!         # while !String::from_utf8_lossy(&request).ends_with('}') {
+//!         #     let read = socket.read(&mut buffer).await.unwrap();
+//!         #     request.extend_from_slice(&buffer[..read]);
+//!         # }
+//!         socket.write_all(response.as_bytes()).await.unwrap();
+//!     }
+//! });
+//!
+//! let client = openai::builder("api_key")
+//!     .base_url(&base_url)
+//!     .retry_policy(RetryPolicy::default().max_attempts(2))
+//!     .build()
+//!     .unwrap();
+//!
+//! let edit = Edit { input: Cow::Borrowed("Hello"), ..Default::default() };
+//! let response: EditResponse = client.create(Some("text-davinci-edit-001"), &edit).await.unwrap();
+//! # }
+//! ```
+
+use std::time::{Duration, SystemTime};
+use hyper::{HeaderMap, StatusCode};
+use rand::Rng;
+use crate::endpoints::ResponseError;
+
+/// The kinds of transport errors which can be considered retryable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryableError {
+    /// The connection could not be established.
+    Connect,
+
+    /// The connection was closed before the response was complete.
+    IncompleteMessage,
+
+    /// The connection was closed, e.g. by an idle pooled connection being reset.
+    Closed,
+
+    /// The request or the response body exceeded the configured timeout.
+    Timeout,
+}
+
+/// A policy which decides whether and when a failed request is sent again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts including the first one. `1` disables retrying.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry, doubled with every further retry.
+    pub base_delay: Duration,
+
+    /// The upper bound of a single delay, including delays requested by the API.
+    pub max_delay: Duration,
+
+    /// The fraction (between 0 and 1) by which each computed delay is randomly shortened.
+    pub jitter: f64,
+
+    /// The response status codes which are retried.
+    pub statuses: Vec<StatusCode>,
+
+    /// The transport errors which are retried.
+    pub errors: Vec<RetryableError>,
+
+    /// Whether delays requested by the `Retry-After` and `x-ratelimit-reset-*` headers are honored.
+    pub respect_retry_after: bool,
+}
+
+impl RetryPolicy {
+    /// Returns a policy which never retries. This is the default policy of a client.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum number of attempts including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sets the upper bound of a single delay.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Sets the fraction by which each computed delay is randomly shortened.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0., 1.);
+        self
+    }
+
+    /// Sets the response status codes which are retried.
+    pub fn statuses(mut self, statuses: Vec<StatusCode>) -> Self {
+        self.statuses = statuses;
+        self
+    }
+
+    /// Sets the transport errors which are retried.
+    pub fn errors(mut self, errors: Vec<RetryableError>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    /// Sets whether delays requested by the API are honored.
+    pub fn respect_retry_after(mut self, respect_retry_after: bool) -> Self {
+        self.respect_retry_after = respect_retry_after;
+        self
+    }
+
+    /// Returns the delay before the next attempt, or `None` if the error should be returned.
+    ///
+    /// # Arguments
+    /// * `attempt` - The number of the attempt which failed, starting at 1.
+    /// * `error` - The error of the failed attempt.
+    pub fn delay(&self, attempt: u32, error: &ResponseError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.is_retryable(error) {
+            return None;
+        }
+
+        let requested = match error {
+            ResponseError::Api(error) if self.respect_retry_after => error.retry_after
+                .or_else(|| {
+                    let rate_limit = &error.rate_limit;
+                    let requests = rate_limit.reset_requests
+                        .filter(|_| rate_limit.remaining_requests == Some(0));
+                    let tokens = rate_limit.reset_tokens
+                        .filter(|_| rate_limit.remaining_tokens == Some(0));
+                    requests.max(tokens)
+                }),
+            _ => None
+        };
+
+        Some(requested.unwrap_or_else(|| self.backoff(attempt)).min(self.max_delay))
+    }
+
+    /// Returns whether the error is retryable under this policy.
+    pub fn is_retryable(&self, error: &ResponseError) -> bool {
+        match error {
+            ResponseError::Api(error) => self.statuses.contains(&error.status),
+            ResponseError::Timeout(_) => self.errors.contains(&RetryableError::Timeout),
+            ResponseError::Hyper(error) => self.errors.iter().any(|kind| match kind {
+                RetryableError::Connect => error.is_connect(),
+                RetryableError::IncompleteMessage => error.is_incomplete_message(),
+                RetryableError::Closed => error.is_closed(),
+                RetryableError::Timeout => error.is_timeout(),
+            }),
+            _ => false
+        }
+    }
+
+    /// Returns the exponential backoff of the attempt, shortened by the jitter.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self.base_delay.saturating_mul(1 << exponent).min(self.max_delay);
+
+        if self.jitter > 0. {
+            delay.mul_f64(1. - rand::thread_rng().gen_range(0. ..=self.jitter))
+        } else {
+            delay
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Retries up to two times on rate limits, server errors, timeouts and connection failures.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+            jitter: 0.25,
+            statuses: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::CONFLICT,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            errors: vec![
+                RetryableError::Connect,
+                RetryableError::IncompleteMessage,
+                RetryableError::Closed,
+                RetryableError::Timeout,
+            ],
+            respect_retry_after: true,
+        }
+    }
+}
+
+/// Parses the delay requested by the `retry-after-ms` or `Retry-After` header.
+/// `Retry-After` is either a number of seconds or an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(millis) = header("retry-after-ms").and_then(|value| value.parse::<f64>().ok()) {
+        return Some(Duration::from_secs_f64(millis.max(0.) / 1000.));
+    }
+
+    let value = header("retry-after")?;
+    match value.parse::<f64>() {
+        Ok(seconds) => Some(Duration::from_secs_f64(seconds.max(0.))),
+        Err(_) => httpdate::parse_http_date(value).ok()
+            .map(|date| date.duration_since(SystemTime::now()).unwrap_or_default())
+    }
+}
@@ -0,0 +1,296 @@
+//! Streaming of incremental responses sent as server-sent events.
+//!
+//! Endpoints which support streaming (e.g. a `Completion` with `stream` set) answer with
+//! `text/event-stream` bodies. Each event carries a JSON chunk in its `data:` lines and the
+//! stream is terminated by a `data: [DONE]` message.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use futures_util::Stream;
+use hyper::body::{Bytes, HttpBody};
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, HeaderMap, Response as HyperResponse, StatusCode};
+use serde::de::DeserializeOwned;
+use tokio::time::Sleep;
+use crate::client::timeout;
+use crate::endpoints::{ApiError, ResponseError, UsageReport};
+use crate::usage::UsageTracker;
+
+/// A stream of the raw chunks of a response body, e.g. synthesized speech.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, ResponseError>> + Send>>;
+
+/// The data of the event which terminates a stream.
+pub const DONE: &str = "[DONE]";
+
+/// A single server-sent event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+    /// The type of the event, set by an `event:` line.
+    pub event: Option<String>,
+
+    /// The data of the event. Multiple `data:` lines are joined by a line feed.
+    pub data: String,
+}
+
+/// An incremental parser of server-sent events which is fed with arbitrary chunks of the body.
+#[derive(Debug, Default)]
+pub struct EventParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Option<String>,
+    skip_line_feed: bool,
+}
+
+impl EventParser {
+    /// Feeds the next chunk of the body and returns the events which were completed by it.
+    ///
+    /// # Example
+    /// ```
+    /// use openai_rs::stream::EventParser;
+    ///
+    /// let mut parser = EventParser::default();
+    /// assert!(parser.feed(b"data: {\"a\":").is_empty());
+    ///
+    /// let events = parser.feed(b"\ndata: 1}\n\ndata: [DONE]\n\n");
+    /// assert_eq!(events[0].data, "{\"a\":\n1}");
+    /// assert_eq!(events[1].data, "[DONE]");
+    /// ```
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        for &byte in chunk {
+            if self.skip_line_feed {
+                self.skip_line_feed = false;
+                if byte == b'\n' {
+                    continue;
+                }
+            }
+
+            match byte {
+                b'\r' | b'\n' => {
+                    self.skip_line_feed = byte == b'\r';
+                    let line = std::mem::take(&mut self.buffer);
+                    if let Some(event) = self.line(&String::from_utf8_lossy(&line)) {
+                        events.push(event);
+                    }
+                },
+                _ => self.buffer.push(byte)
+            }
+        }
+
+        events
+    }
+
+    /// Finishes the stream and returns the pending event, if the body did not end with a blank line.
+    pub fn finish(&mut self) -> Option<Event> {
+        if !self.buffer.is_empty() {
+            let line = std::mem::take(&mut self.buffer);
+            if let Some(event) = self.line(&String::from_utf8_lossy(&line)) {
+                return Some(event);
+            }
+        }
+
+        self.line("")
+    }
+
+    /// Processes a complete line and returns the event if the line dispatched it.
+    fn line(&mut self, line: &str) -> Option<Event> {
+        if line.is_empty() {
+            let data = self.data.take();
+            let event = self.event.take();
+            return data.map(|data| Event { event, data });
+        }
+
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, "")
+        };
+
+        match field {
+            "event" => self.event = Some(value.to_owned()),
+            "data" => match &mut self.data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                },
+                None => self.data = Some(value.to_owned())
+            },
+            _ => {}
+        }
+
+        None
+    }
+}
+
+/// A stream of the chunks of a streamed response.
+///
+/// Each event is deserialized into `T`. Error objects sent in the middle of the stream
+/// are yielded as [`ResponseError::Api`], after which the stream ends.
+/// The usage reported by chunks is added to the usage tracker of the client.
+pub struct EventStream<T> {
+    body: Body,
+    status: StatusCode,
+    headers: HeaderMap,
+    parser: EventParser,
+    pending: VecDeque<Result<T, ResponseError>>,
+    done: bool,
+    read_timeout: Option<Duration>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    usage: Option<(Arc<UsageTracker>, String)>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned + UsageReport> EventStream<T> {
+    /// Returns a stream of the events of the response body.
+    /// The read timeout applies to the wait for each chunk of the body.
+    pub(crate) fn new(response: HyperResponse<Body>, read_timeout: Option<Duration>) -> Self {
+        let (parts, body) = response.into_parts();
+
+        Self {
+            body,
+            status: parts.status,
+            headers: parts.headers,
+            parser: EventParser::default(),
+            pending: VecDeque::new(),
+            done: false,
+            read_timeout,
+            sleep: None,
+            usage: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Records the usage reported by the chunks for the tenant.
+    pub(crate) fn with_usage(mut self, tracker: Arc<UsageTracker>, tenant: &str) -> Self {
+        self.usage = Some((tracker, tenant.to_owned()));
+        self
+    }
+
+    /// Returns a stream which only yields the given item.
+    pub(crate) fn from_single(item: Result<T, ResponseError>) -> Self {
+        let mut stream = Self::new(HyperResponse::new(Body::empty()), None);
+        stream.pending.push_back(item);
+        stream.done = true;
+        stream
+    }
+
+    /// Handles a complete event by queueing its chunk or error.
+    fn handle(&mut self, event: Event) {
+        if self.done {
+            return;
+        }
+
+        if event.data == DONE {
+            self.done = true;
+            return;
+        }
+
+        let value = match serde_json::from_str::<serde_json::Value>(&event.data) {
+            Ok(value) => value,
+            Err(error) if event.event.as_deref() != Some("error") => {
+                self.pending.push_back(Err(error.into()));
+                return;
+            },
+            Err(_) => serde_json::Value::Null
+        };
+
+        if event.event.as_deref() == Some("error") || value.get("error").is_some() {
+            let error = ApiError::new(self.status, &self.headers, event.data.as_bytes());
+            self.pending.push_back(Err(ResponseError::Api(Box::new(error))));
+            self.done = true;
+            return;
+        }
+
+        let chunk = serde_json::from_value::<T>(value).map_err(ResponseError::from);
+        if let (Some((tracker, tenant)), Ok(chunk)) = (&self.usage, &chunk) {
+            if let Some(usage) = chunk.usage() {
+                tracker.record(tenant, usage);
+            }
+        }
+
+        self.pending.push_back(chunk);
+    }
+}
+
+// The chunks are never pinned, so the stream can be moved regardless of `T`.
+impl<T> Unpin for EventStream<T> {}
+
+impl<T: DeserializeOwned + UsageReport> Stream for EventStream<T> {
+    type Item = Result<T, ResponseError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(item) = this.pending.pop_front() {
+                return Poll::Ready(Some(item));
+            }
+
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            match Pin::new(&mut this.body).poll_data(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    this.sleep = None;
+                    for event in this.parser.feed(&chunk) {
+                        this.handle(event);
+                    }
+                },
+                Poll::Ready(Some(Err(error))) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(error.into())));
+                },
+                Poll::Ready(None) => {
+                    if let Some(event) = this.parser.finish() {
+                        this.handle(event);
+                    }
+                    this.done = true;
+                },
+                Poll::Pending => {
+                    if let Some(duration) = this.read_timeout {
+                        let sleep = this.sleep
+                            .get_or_insert_with(|| Box::pin(tokio::time::sleep(duration)));
+
+                        if sleep.as_mut().poll(cx).is_ready() {
+                            this.done = true;
+                            return Poll::Ready(Some(Err(ResponseError::Timeout(duration))));
+                        }
+                    }
+
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether the content type of the response is `text/event-stream`.
+pub(crate) fn is_event_stream(headers: &HeaderMap) -> bool {
+    headers.get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim_start().starts_with("text/event-stream"))
+        .unwrap_or(false)
+}
+
+/// Returns a stream of the chunks of the body, which fails if a chunk exceeds the read timeout.
+pub(crate) fn byte_stream(body: Body, read_timeout: Option<Duration>) -> ByteStream {
+    Box::pin(futures_util::stream::unfold(Some(body), move |body| async move {
+        let mut body = body?;
+        match timeout(read_timeout, body.data()).await {
+            Ok(Some(Ok(chunk))) => Some((Ok(chunk), Some(body))),
+            Ok(Some(Err(error))) => Some((Err(ResponseError::from(error)), None)),
+            Ok(None) => None,
+            Err(error) => Some((Err(error), None))
+        }
+    }))
+}
@@ -0,0 +1,515 @@
+//! Offline byte-pair encoding to count tokens and look up token ids without the API.
+//!
+//! A [`Tokenizer`] implements one of the [`Encoding`]s of the OpenAI models: `r50k_base` (GPT-2 and
+//! GPT-3), `p50k_base` (Codex and `text-davinci-002/003`), `cl100k_base` (GPT-3.5, GPT-4 and the
+//! embedding models) and `o200k_base` (GPT-4o and newer). The module is compiled with the
+//! `tokenizer` feature. The vocabularies are bundled with the `tokenizer-vocab` feature,
+//! which reads them from `vocab/<encoding>.tiktoken` next to the manifest, as published at
+//! `https://openaipublic.blob.core.windows.net/encodings/<encoding>.tiktoken`.
+//! Without the feature, a vocabulary is loaded with [`Tokenizer::from_tiktoken`].
+//!
+//! # Example
+//! ```
+//! use std::collections::HashMap;
+//! use openai_rs::tokenizer::{Encoding, Tokenizer};
+//!
+//! // A tiny vocabulary of all bytes and a few merges, in place of a bundled one.
+//! let mut ranks: HashMap<Vec<u8>, u32> = (0..=255u8).map(|byte| (vec![byte], byte as u32)).collect();
+//! ranks.insert(b"ll".to_vec(), 256);
+//! ranks.insert(b"he".to_vec(), 257);
+//! ranks.insert(b"hell".to_vec(), 258);
+//!
+//! let tokenizer = Tokenizer::new(Encoding::Cl100kBase, ranks);
+//!
+//! let tokens = tokenizer.encode("hello hello");
+//! assert_eq!(tokens, [258, 111, 32, 258, 111]);
+//! assert_eq!(tokenizer.count("hello hello"), 5);
+//! assert_eq!(tokenizer.decode(&tokens), "hello hello");
+//! ```
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// The byte-pair encodings used by the OpenAI models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// The encoding of GPT-2 and the GPT-3 base models, e.g. `davinci`, also known as `gpt2`.
+    R50kBase,
+
+    /// The encoding of the Codex models and `text-davinci-002/003`.
+    P50kBase,
+
+    /// The encoding of GPT-3.5, GPT-4 and the embedding models.
+    Cl100kBase,
+
+    /// The encoding of GPT-4o, the o-series and newer models.
+    O200kBase,
+}
+
+impl Encoding {
+    /// Returns the name of the encoding, e.g. `cl100k_base`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::R50kBase => "r50k_base",
+            Self::P50kBase => "p50k_base",
+            Self::Cl100kBase => "cl100k_base",
+            Self::O200kBase => "o200k_base",
+        }
+    }
+
+    /// Returns the encoding of a model, including its snapshots and fine-tuned models,
+    /// or `None` if the model is unknown.
+    ///
+    /// # Example
+    /// ```
+    /// use openai_rs::tokenizer::Encoding;
+    ///
+    /// assert_eq!(Encoding::for_model("gpt-4o-2024-08-06"), Some(Encoding::O200kBase));
+    /// assert_eq!(Encoding::for_model("ft:gpt-3.5-turbo-0125:acme::abc123"), Some(Encoding::Cl100kBase));
+    /// assert_eq!(Encoding::for_model("text-davinci-003"), Some(Encoding::P50kBase));
+    /// assert_eq!(Encoding::for_model("davinci"), Some(Encoding::R50kBase));
+    /// ```
+    pub fn for_model(model: &str) -> Option<Self> {
+        let model = model.strip_prefix("ft:").unwrap_or(model);
+        MODEL_PREFIXES.iter()
+            .find(|(prefix, _)| model.starts_with(prefix))
+            .map(|(_, encoding)| *encoding)
+    }
+
+    /// Returns the special tokens of the encoding with their ids.
+    pub fn special_tokens(&self) -> &'static [(&'static str, u32)] {
+        match self {
+            Self::R50kBase | Self::P50kBase => &[("<|endoftext|>", 50256)],
+            Self::Cl100kBase => &[
+                ("<|endoftext|>", 100257),
+                ("<|fim_prefix|>", 100258),
+                ("<|fim_middle|>", 100259),
+                ("<|fim_suffix|>", 100260),
+                ("<|endofprompt|>", 100276),
+            ],
+            Self::O200kBase => &[("<|endoftext|>", 199999), ("<|endofprompt|>", 200018)],
+        }
+    }
+
+    /// Returns the length in bytes of the next piece of the text, split as by the pattern of the encoding.
+    /// The unicode classes of the patterns are approximated with the properties of [`char`].
+    fn split(&self, text: &str) -> usize {
+        let chars: Vec<(usize, char)> = text.char_indices().take(PIECE_LOOKAHEAD + 1).collect();
+        let scanner = Scanner { text, chars: &chars };
+
+        let end = match self {
+            Self::R50kBase | Self::P50kBase => scanner.contraction(0, false)
+                .or_else(|| scanner.spaced(is_letter))
+                .or_else(|| scanner.spaced(|c| c.is_numeric()))
+                .or_else(|| scanner.spaced(is_other))
+                .or_else(|| scanner.whitespace(false)),
+            Self::Cl100kBase => scanner.contraction(0, true)
+                .or_else(|| scanner.prefixed_letters())
+                .or_else(|| scanner.numbers())
+                .or_else(|| scanner.spaced(is_other).map(|end| scanner.skip(end, |c| c == '\r' || c == '\n')))
+                .or_else(|| scanner.whitespace(true)),
+            Self::O200kBase => scanner.cased_word()
+                .or_else(|| scanner.numbers())
+                .or_else(|| scanner.spaced(is_other).map(|end| scanner.skip(end, |c| c == '\r' || c == '\n' || c == '/')))
+                .or_else(|| scanner.whitespace(true)),
+        };
+
+        // Every character is matched by one of the patterns, but the splitting must always advance.
+        end.filter(|end| *end > 0)
+            .map(|end| scanner.offset(end.min(PIECE_LOOKAHEAD)))
+            .unwrap_or_else(|| chars.get(1).map(|(offset, _)| *offset).unwrap_or(text.len()))
+    }
+}
+
+/// The model prefixes with their encoding, more specific prefixes first.
+const MODEL_PREFIXES: &[(&str, Encoding)] = &[
+    ("gpt-4o", Encoding::O200kBase),
+    ("gpt-4.1", Encoding::O200kBase),
+    ("gpt-4.5", Encoding::O200kBase),
+    ("gpt-5", Encoding::O200kBase),
+    ("chatgpt-4o", Encoding::O200kBase),
+    ("o1", Encoding::O200kBase),
+    ("o3", Encoding::O200kBase),
+    ("o4", Encoding::O200kBase),
+    ("gpt-4", Encoding::Cl100kBase),
+    ("gpt-3.5", Encoding::Cl100kBase),
+    ("text-embedding-ada-002", Encoding::Cl100kBase),
+    ("text-embedding-3", Encoding::Cl100kBase),
+    ("davinci-002", Encoding::Cl100kBase),
+    ("babbage-002", Encoding::Cl100kBase),
+    ("text-davinci-002", Encoding::P50kBase),
+    ("text-davinci-003", Encoding::P50kBase),
+    ("text-davinci-edit", Encoding::P50kBase),
+    ("code-", Encoding::P50kBase),
+    ("text-", Encoding::R50kBase),
+    ("davinci", Encoding::R50kBase),
+    ("curie", Encoding::R50kBase),
+    ("babbage", Encoding::R50kBase),
+    ("ada", Encoding::R50kBase),
+    ("gpt2", Encoding::R50kBase),
+];
+
+/// The number of characters the splitting looks at, which bounds the length of a piece.
+/// Longer runs of the same class are split into several pieces, which only affects
+/// the encoding of texts like a long line of dashes.
+const PIECE_LOOKAHEAD: usize = 1024;
+
+/// Whether the character is a letter, `\p{L}`.
+fn is_letter(c: char) -> bool {
+    c.is_alphabetic() && !is_mark(c)
+}
+
+/// Whether the character is neither whitespace, a letter nor a number, `[^\s\p{L}\p{N}]`.
+fn is_other(c: char) -> bool {
+    !c.is_whitespace() && !is_letter(c) && !c.is_numeric()
+}
+
+/// Matches the alternatives of the splitting patterns at the start of the text.
+/// Positions are indices into the characters and matches return the end position.
+struct Scanner<'a> {
+    text: &'a str,
+    chars: &'a [(usize, char)],
+}
+
+impl Scanner<'_> {
+    fn get(&self, position: usize) -> Option<char> {
+        self.chars.get(position).map(|(_, c)| *c)
+    }
+
+    fn offset(&self, position: usize) -> usize {
+        self.chars.get(position).map(|(offset, _)| *offset).unwrap_or(self.text.len())
+    }
+
+    /// Returns the position after the run of characters of the class starting at the position.
+    fn skip(&self, mut position: usize, class: impl Fn(char) -> bool) -> usize {
+        while self.get(position).is_some_and(&class) {
+            position += 1;
+        }
+        position
+    }
+
+    /// `'s|'t|'re|'ve|'m|'ll|'d`, optionally case-insensitive.
+    fn contraction(&self, position: usize, case_insensitive: bool) -> Option<usize> {
+        if self.get(position) != Some('\'') {
+            return None;
+        }
+
+        ["s", "t", "re", "ve", "m", "ll", "d"].iter().find_map(|suffix| {
+            let matches = suffix.chars().enumerate().all(|(i, expected)| match self.get(position + 1 + i) {
+                Some(c) if case_insensitive => c.to_ascii_lowercase() == expected,
+                Some(c) => c == expected,
+                None => false
+            });
+            matches.then(|| position + 1 + suffix.len())
+        })
+    }
+
+    /// ` ?<class>+`
+    fn spaced(&self, class: impl Fn(char) -> bool) -> Option<usize> {
+        let start = if self.get(0) == Some(' ') && self.get(1).is_some_and(&class) { 1 } else { 0 };
+        let end = self.skip(start, class);
+        (end > start).then_some(end)
+    }
+
+    /// `[^\r\n\p{L}\p{N}]?\p{L}+`
+    fn prefixed_letters(&self) -> Option<usize> {
+        let prefixed = self.get(0).is_some_and(|c| c != '\r' && c != '\n' && !is_letter(c) && !c.is_numeric())
+            && self.get(1).is_some_and(is_letter);
+        let start = usize::from(prefixed);
+        let end = self.skip(start, is_letter);
+        (end > start).then_some(end)
+    }
+
+    /// `\p{N}{1,3}`
+    fn numbers(&self) -> Option<usize> {
+        let end = (0..3).take_while(|i| self.get(*i).is_some_and(char::is_numeric)).count();
+        (end > 0).then_some(end)
+    }
+
+    /// `[^\r\n\p{L}\p{N}]?(Upper*Lower+|Upper+Lower*)(?i:'s|'t|'re|'ve|'m|'ll|'d)?`, where other letters
+    /// and marks, which are neither upper- nor lowercase, belong to both classes.
+    fn cased_word(&self) -> Option<usize> {
+        let is_upper = |c: char| (is_letter(c) && (!c.is_lowercase() || is_lowercase_modifier(c))) || is_mark(c);
+        let is_lower = |c: char| (is_letter(c) && !c.is_uppercase() && !is_titlecase(c)) || is_mark(c);
+
+        // Upper*Lower+, where Upper* backtracks until Lower+ matches.
+        let lower_word = |start: usize| -> Option<usize> {
+            let upper = self.skip(start, is_upper);
+            (start..=upper).rev()
+                .find(|position| self.get(*position).is_some_and(is_lower))
+                .map(|position| self.skip(position, is_lower))
+        };
+        // Upper+Lower*
+        let upper_word = |start: usize| -> Option<usize> {
+            let upper = self.skip(start, is_upper);
+            (upper > start).then(|| self.skip(upper, is_lower))
+        };
+
+        let prefixed = self.get(0).is_some_and(|c| c != '\r' && c != '\n' && !is_letter(c) && !c.is_numeric());
+        let word = |alternative: &dyn Fn(usize) -> Option<usize>| {
+            prefixed.then(|| alternative(1)).flatten().or_else(|| alternative(0))
+        };
+
+        let end = word(&lower_word).or_else(|| word(&upper_word))?;
+        Some(self.contraction(end, true).unwrap_or(end))
+    }
+
+    /// `\s*[\r\n]+|\s+(?!\S)|\s+`, the first alternative only with `newlines`.
+    fn whitespace(&self, newlines: bool) -> Option<usize> {
+        let end = self.skip(0, char::is_whitespace);
+        if end == 0 {
+            return None;
+        }
+
+        if newlines {
+            let last_newline = (0..end).rev().find(|i| matches!(self.get(*i), Some('\r' | '\n')));
+            if let Some(last_newline) = last_newline {
+                return Some(last_newline + 1);
+            }
+        }
+
+        // The last whitespace is left to prefix the following piece.
+        if end > 1 && self.get(end).is_some() {
+            Some(end - 1)
+        } else {
+            Some(end)
+        }
+    }
+}
+
+/// Whether the character is a mark, `\p{M}`, approximated by the combining diacritics and the
+/// vowel signs of the common scripts, which [`char::is_alphabetic`] counts as letters.
+fn is_mark(c: char) -> bool {
+    match c {
+        // Devanagari to Malayalam share the layout of their blocks.
+        '\u{0900}'..='\u{0D7F}' => matches!(c as u32 & 0x7F, 0x00..=0x03 | 0x3A..=0x3C | 0x3E..=0x4F | 0x51..=0x57 | 0x62..=0x63),
+        '\u{0300}'..='\u{036F}' | '\u{0483}'..='\u{0489}' | '\u{0591}'..='\u{05BD}' | '\u{05BF}' | '\u{05C1}'..='\u{05C2}'
+        | '\u{05C4}'..='\u{05C5}' | '\u{05C7}' | '\u{0610}'..='\u{061A}' | '\u{064B}'..='\u{065F}' | '\u{0670}'
+        | '\u{06D6}'..='\u{06DC}' | '\u{06DF}'..='\u{06E4}' | '\u{06E7}'..='\u{06E8}' | '\u{06EA}'..='\u{06ED}'
+        | '\u{0E31}' | '\u{0E34}'..='\u{0E3A}' | '\u{0E47}'..='\u{0E4E}' | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}' | '\u{20D0}'..='\u{20FF}' | '\u{302A}'..='\u{302F}' | '\u{3099}'..='\u{309A}'
+        | '\u{FE00}'..='\u{FE0F}' | '\u{FE20}'..='\u{FE2F}' => true,
+        _ => false
+    }
+}
+
+/// Whether the character is a titlecase letter, `\p{Lt}`, which only belongs to the uppercase class.
+fn is_titlecase(c: char) -> bool {
+    matches!(c, '\u{01C5}' | '\u{01C8}' | '\u{01CB}' | '\u{01F2}' | '\u{1F88}'..='\u{1F8F}' | '\u{1F98}'..='\u{1F9F}'
+        | '\u{1FA8}'..='\u{1FAF}' | '\u{1FBC}' | '\u{1FCC}' | '\u{1FFC}')
+}
+
+/// Whether the character is lowercase but a modifier or other letter, `\p{Lm}` or `\p{Lo}`,
+/// which belong to both the upper- and the lowercase class.
+fn is_lowercase_modifier(c: char) -> bool {
+    matches!(c, '\u{00AA}' | '\u{00BA}' | '\u{02B0}'..='\u{02B8}' | '\u{02C0}'..='\u{02C1}' | '\u{02E0}'..='\u{02E4}'
+        | '\u{037A}' | '\u{1D2C}'..='\u{1D6A}' | '\u{1D78}' | '\u{1D9B}'..='\u{1DBF}' | '\u{2071}' | '\u{207F}'
+        | '\u{2090}'..='\u{209C}' | '\u{2C7C}'..='\u{2C7D}' | '\u{A69C}'..='\u{A69D}' | '\u{A770}'
+        | '\u{A7F2}'..='\u{A7F4}' | '\u{A7F8}'..='\u{A7F9}' | '\u{AB5C}'..='\u{AB5F}' | '\u{AB69}')
+}
+
+/// A byte-pair encoding tokenizer with the vocabulary of an [`Encoding`].
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    encoding: Encoding,
+    encoder: HashMap<Vec<u8>, u32>,
+    decoder: HashMap<u32, Vec<u8>>,
+}
+
+impl Tokenizer {
+    /// Returns a tokenizer with the ranks of the byte sequences of the vocabulary,
+    /// where a lower rank is merged first. The ranks must contain every single byte.
+    pub fn new(encoding: Encoding, ranks: HashMap<Vec<u8>, u32>) -> Self {
+        let mut decoder: HashMap<u32, Vec<u8>> = ranks.iter()
+            .map(|(bytes, rank)| (*rank, bytes.clone()))
+            .collect();
+        for (token, id) in encoding.special_tokens() {
+            decoder.insert(*id, token.as_bytes().to_vec());
+        }
+
+        Self { encoding, encoder: ranks, decoder }
+    }
+
+    /// Returns a tokenizer with the vocabulary in the `.tiktoken` format,
+    /// a line with the base64 encoded bytes and the rank per token.
+    pub fn from_tiktoken(encoding: Encoding, vocabulary: &[u8]) -> Result<Self, Error> {
+        let invalid = |line: usize| Error::new(
+            ErrorKind::InvalidData, format!("invalid {} vocabulary at line {}", encoding.name(), line + 1)
+        );
+
+        let mut ranks = HashMap::new();
+        for (number, line) in vocabulary.split(|byte| *byte == b'\n').enumerate() {
+            let line = line.trim_ascii();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split(|byte| *byte == b' ');
+            let (token, rank) = parts.next().zip(parts.next()).ok_or_else(|| invalid(number))?;
+            let token = STANDARD.decode(token).map_err(|_| invalid(number))?;
+            let rank = std::str::from_utf8(rank).ok()
+                .and_then(|rank| rank.parse().ok())
+                .ok_or_else(|| invalid(number))?;
+            ranks.insert(token, rank);
+        }
+
+        Ok(Self::new(encoding, ranks))
+    }
+
+    /// Returns the tokenizer of the encoding with the bundled vocabulary,
+    /// which is loaded once on the first use.
+    ///
+    /// # Example
+    /// ```
+    /// use openai_rs::tokenizer::{Encoding, Tokenizer};
+    ///
+    /// let tokenizer = Tokenizer::get(Encoding::Cl100kBase);
+    /// assert_eq!(tokenizer.encode("hello world"), [15339, 1917]);
+    /// assert_eq!(tokenizer.encode("こんにちは世界、元気ですか？"), [90115, 3574, 244, 98220, 5486, 24186, 95221, 38641, 32149, 11571]);
+    /// assert_eq!(tokenizer.encode("مرحبا بالعالم"), [10386, 11318, 30925, 22071, 5821, 28946, 32482, 24102, 32482, 10386]);
+    ///
+    /// let tokenizer = Tokenizer::get(Encoding::O200kBase);
+    /// assert_eq!(tokenizer.encode("hello world"), [24912, 2375]);
+    /// assert_eq!(tokenizer.encode("Привет, мир! Как дела?"), [23881, 131903, 11, 37934, 0, 26029, 78857, 30]);
+    /// assert_eq!(tokenizer.decode(&[23881, 131903, 11, 37934, 0, 26029, 78857, 30]), "Привет, мир! Как дела?");
+    /// ```
+    #[cfg(feature = "tokenizer-vocab")]
+    pub fn get(encoding: Encoding) -> &'static Tokenizer {
+        use std::sync::OnceLock;
+
+        static TOKENIZERS: [OnceLock<Tokenizer>; 4] = [OnceLock::new(), OnceLock::new(), OnceLock::new(), OnceLock::new()];
+        let (index, vocabulary): (usize, &[u8]) = match encoding {
+            Encoding::R50kBase => (0, include_bytes!("../vocab/r50k_base.tiktoken")),
+            Encoding::P50kBase => (1, include_bytes!("../vocab/p50k_base.tiktoken")),
+            Encoding::Cl100kBase => (2, include_bytes!("../vocab/cl100k_base.tiktoken")),
+            Encoding::O200kBase => (3, include_bytes!("../vocab/o200k_base.tiktoken")),
+        };
+
+        TOKENIZERS[index].get_or_init(|| {
+            Self::from_tiktoken(encoding, vocabulary).expect("the bundled vocabulary is valid")
+        })
+    }
+
+    /// Returns the tokenizer of a model with the bundled vocabulary, or `None` if the model is unknown.
+    ///
+    /// # Example
+    /// ```
+    /// use openai_rs::tokenizer::{Encoding, Tokenizer};
+    ///
+    /// let tokenizer = Tokenizer::for_model("gpt-4-0613").unwrap();
+    /// assert_eq!(tokenizer.encoding(), Encoding::Cl100kBase);
+    ///
+    /// // Runs longer than the lookahead of the splitting are encoded like a single piece.
+    /// let spaces = " ".repeat(3000);
+    /// assert_eq!(tokenizer.count(&spaces), 24);
+    /// assert_eq!(tokenizer.count(&format!("a{}\n\nb", " ".repeat(2000))), 19);
+    /// let digits = "1".repeat(3000);
+    /// assert_eq!(tokenizer.count(&digits), 1000);
+    /// assert_eq!(tokenizer.decode(&tokenizer.encode(&digits)), digits);
+    ///
+    /// assert!(Tokenizer::for_model("unknown-model").is_none());
+    /// ```
+    #[cfg(feature = "tokenizer-vocab")]
+    pub fn for_model(model: &str) -> Option<&'static Tokenizer> {
+        Encoding::for_model(model).map(Self::get)
+    }
+
+    /// Returns the encoding of the vocabulary of the tokenizer.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Encodes the text into token ids. Special tokens in the text are encoded as plain text,
+    /// so untrusted input cannot inject them.
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        let mut tokens = Vec::new();
+        self.encode_ordinary(text, &mut tokens);
+        tokens
+    }
+
+    /// Encodes the text into token ids, where special tokens like `<|endoftext|>` are encoded
+    /// as their id.
+    pub fn encode_with_special_tokens(&self, text: &str) -> Vec<u32> {
+        let mut tokens = Vec::new();
+        let mut rest = text;
+
+        loop {
+            let next = self.encoding.special_tokens().iter()
+                .filter_map(|(token, id)| rest.find(token).map(|start| (start, *token, *id)))
+                .min_by_key(|(start, _, _)| *start);
+
+            match next {
+                Some((start, token, id)) => {
+                    self.encode_ordinary(&rest[..start], &mut tokens);
+                    tokens.push(id);
+                    rest = &rest[start + token.len()..];
+                }
+                None => {
+                    self.encode_ordinary(rest, &mut tokens);
+                    return tokens;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of tokens of the text.
+    pub fn count(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+
+    /// Decodes the token ids into text, replacing invalid UTF-8 and skipping unknown ids.
+    pub fn decode(&self, tokens: &[u32]) -> String {
+        String::from_utf8_lossy(&self.decode_bytes(tokens)).into_owned()
+    }
+
+    /// Decodes the token ids into bytes, skipping unknown ids. A token may be part of
+    /// a multi-byte character, so the bytes of a slice of the tokens are not always valid UTF-8.
+    pub fn decode_bytes(&self, tokens: &[u32]) -> Vec<u8> {
+        tokens.iter()
+            .filter_map(|token| self.decoder.get(token))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the id of the token for the bytes, e.g. to bias it with `logit_bias`.
+    pub fn token_id(&self, bytes: &[u8]) -> Option<u32> {
+        self.encoder.get(bytes).copied()
+    }
+
+    fn encode_ordinary(&self, mut text: &str, tokens: &mut Vec<u32>) {
+        while !text.is_empty() {
+            let end = self.encoding.split(text);
+            let piece = &text.as_bytes()[..end];
+            match self.encoder.get(piece) {
+                Some(token) => tokens.push(*token),
+                None => self.merge(piece, tokens),
+            }
+            text = &text[end..];
+        }
+    }
+
+    /// Merges the bytes of the piece pairwise, always merging the pair of the lowest rank first.
+    fn merge(&self, piece: &[u8], tokens: &mut Vec<u32>) {
+        let rank = |parts: &[usize], i: usize| -> Option<u32> {
+            parts.get(i + 2).and_then(|end| self.encoder.get(&piece[parts[i]..*end]).copied())
+        };
+
+        // The start of each part, followed by the end of the piece.
+        let mut parts: Vec<usize> = (0..=piece.len()).collect();
+        loop {
+            let lowest = (0..parts.len().saturating_sub(2))
+                .filter_map(|i| rank(&parts, i).map(|rank| (rank, i)))
+                .min();
+
+            match lowest {
+                Some((_, i)) => { parts.remove(i + 1); }
+                None => break
+            }
+        }
+
+        tokens.extend(parts.windows(2).filter_map(|part| self.encoder.get(&piece[part[0]..part[1]])));
+    }
+}
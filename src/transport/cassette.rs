@@ -0,0 +1,413 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hyper::body::Bytes;
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper::{Body, HeaderMap, Request, Response, StatusCode};
+use hyper::http::request::Parts;
+use serde::{Deserialize, Serialize};
+use crate::client::ORGANIZATION_HEADER;
+use crate::endpoints::ResponseError;
+use crate::transport::{Transport, TransportFuture};
+
+/// The value which replaces the redacted headers in a cassette.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// The headers which are always redacted in a cassette, as they carry the credentials of the client.
+pub const REDACTED_HEADERS: &[&str] = &["authorization", ORGANIZATION_HEADER, "openai-project"];
+
+/// The response headers which are not recorded in a cassette, as they carry session state.
+const DROPPED_HEADERS: &[&str] = &["set-cookie"];
+
+/// The placeholder which replaces the random boundary of multipart bodies in a cassette,
+/// so uploads match regardless of their boundary.
+const BOUNDARY_PLACEHOLDER: &str = "openai-rs-cassette-boundary";
+
+/// How the [`Client`](crate::client::Client) uses a cassette, see
+/// [`ClientBuilder::cassette`](crate::client::ClientBuilder::cassette).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Sends the requests and records them with their responses, replacing an existing cassette.
+    Record,
+
+    /// Serves the requests from an existing cassette without sending them.
+    Replay,
+
+    /// Replays the cassette if it exists and records it otherwise.
+    Once,
+}
+
+/// The recorded requests and responses of a [`RecordingTransport`], stored as a JSON file.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// A request and the response it was answered with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: CassetteRequest,
+    pub response: CassetteResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteRequest {
+    pub method: String,
+    pub uri: String,
+
+    /// The headers of the request, with the [`REDACTED_HEADERS`] and those of
+    /// [`RecordingTransport::redact_header`] redacted.
+    pub headers: Vec<(String, String)>,
+    #[serde(flatten)]
+    pub body: CassetteBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteResponse {
+    pub status: u16,
+
+    /// The headers of the response, redacted like those of the request and without `Set-Cookie`.
+    pub headers: Vec<(String, String)>,
+    #[serde(flatten)]
+    pub body: CassetteBody,
+}
+
+/// A body, stored as text if it is valid UTF-8 and base64 encoded otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CassetteBody {
+    #[serde(rename = "body")]
+    Text(String),
+    #[serde(rename = "body_base64")]
+    Base64(String),
+}
+
+impl Cassette {
+    /// Reads the cassette from the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ResponseError> {
+        let content = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    /// Writes the cassette to the file, creating its directory if needed.
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<(), ResponseError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let content = serde_json::to_vec_pretty(self)?;
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+}
+
+impl CassetteBody {
+    pub(crate) fn new(body: &[u8]) -> Self {
+        match std::str::from_utf8(body) {
+            Ok(text) => Self::Text(text.to_owned()),
+            Err(_) => Self::Base64(STANDARD.encode(body)),
+        }
+    }
+
+    /// Returns the bytes of the body, which are empty if the base64 encoding is malformed.
+    pub fn to_bytes(&self) -> Bytes {
+        match self {
+            Self::Text(text) => Bytes::copy_from_slice(text.as_bytes()),
+            Self::Base64(encoded) => STANDARD.decode(encoded).map(Bytes::from).unwrap_or_default(),
+        }
+    }
+
+    /// Returns whether the bodies are equal, comparing JSON bodies by their value.
+    fn matches(&self, other: &CassetteBody) -> bool {
+        if let (Self::Text(this), Self::Text(other)) = (self, other) {
+            let values = serde_json::from_str::<serde_json::Value>(this).ok()
+                .zip(serde_json::from_str::<serde_json::Value>(other).ok());
+            if let Some((this, other)) = values {
+                return this == other;
+            }
+        }
+
+        self == other
+    }
+}
+
+impl CassetteRequest {
+    /// Returns the request, redacting the named headers, which are lowercase, and the boundary
+    /// of a multipart body.
+    fn new(parts: &Parts, body: &[u8], redacted: &[String]) -> Self {
+        let mut body = body.to_vec();
+
+        let boundary = parts.headers.get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once("boundary="))
+            .map(|(_, boundary)| boundary.trim_matches('"').to_owned());
+        let mut headers = redact(header_pairs(&parts.headers), redacted);
+        if let Some(boundary) = boundary.filter(|boundary| !boundary.is_empty()) {
+            body = replace(&body, boundary.as_bytes(), BOUNDARY_PLACEHOLDER.as_bytes());
+            for (_, value) in headers.iter_mut() {
+                *value = value.replace(&boundary, BOUNDARY_PLACEHOLDER);
+            }
+        }
+        Self {
+            method: parts.method.to_string(),
+            uri: parts.uri.to_string(),
+            headers,
+            body: CassetteBody::new(&body),
+        }
+    }
+
+    fn matches(&self, other: &CassetteRequest) -> bool {
+        self.method == other.method && self.uri == other.uri && self.body.matches(&other.body)
+    }
+}
+
+impl CassetteResponse {
+    fn to_response(&self) -> Result<Response<Body>, ResponseError> {
+        let mut response = Response::new(Body::from(self.body.to_bytes()));
+        *response.status_mut() = StatusCode::from_u16(self.status)
+            .map_err(|error| ResponseError::Transport(error.into()))?;
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                response.headers_mut().append(name, value);
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+/// A transport which sends the requests through another transport and records them
+/// with their responses to a cassette file, which is rewritten after every response.
+/// The bodies of the responses are read completely before they are returned.
+/// The [`REDACTED_HEADERS`] of the requests and responses are replaced by [`REDACTED`].
+#[derive(Debug)]
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+    redacted: Vec<String>,
+    cassette: tokio::sync::Mutex<Cassette>,
+}
+
+impl RecordingTransport {
+    /// Returns a transport which records to the file, replacing an existing cassette.
+    pub fn new<T: Transport + 'static>(inner: T, path: impl Into<PathBuf>) -> Self {
+        Self::with_shared(Arc::new(inner), path)
+    }
+
+    pub(crate) fn with_shared(inner: Arc<dyn Transport>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            redacted: REDACTED_HEADERS.iter().map(|name| name.to_string()).collect(),
+            cassette: tokio::sync::Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// Also redacts the header in the cassette, e.g. a custom header with an API key.
+    pub fn redact_header(mut self, name: &str) -> Self {
+        self.redacted.push(name.to_ascii_lowercase());
+        self
+    }
+
+    /// Returns the file the cassette is recorded to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send(&self, request: Request<Body>) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            let recorded = CassetteRequest::new(&parts, &body, &self.redacted);
+            let request = Request::from_parts(parts, Body::from(body));
+
+            let response = self.inner.send(request).await?;
+            let (parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+
+            let interaction = Interaction {
+                request: recorded,
+                response: CassetteResponse {
+                    status: parts.status.as_u16(),
+                    headers: redact(header_pairs(&parts.headers), &self.redacted),
+                    body: CassetteBody::new(&body),
+                },
+            };
+
+            let mut cassette = self.cassette.lock().await;
+            cassette.interactions.push(interaction);
+            cassette.save(&self.path).await?;
+
+            Ok(Response::from_parts(parts, Body::from(body)))
+        })
+    }
+}
+
+/// A transport which serves the requests from a cassette instead of sending them.
+/// Each interaction is served once, in the order of the cassette among the matching ones.
+/// A request without a matching interaction fails with [`ResponseError::Transport`].
+///
+/// Requests match if their method, uri and body are equal, where JSON bodies are compared
+/// by value and the headers are ignored.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use hyper::StatusCode;
+/// use openai_rs::endpoints::edits::Edit;
+/// use openai_rs::openai;
+/// use openai_rs::transport::{Cassette, CassetteMode, MockResponse, MockTransport, REDACTED};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let path = std::env::temp_dir().join("openai-rs-cassette-example.json");
+/// let transport = MockTransport::new();
+/// transport.push(MockResponse::json(StatusCode::OK, &serde_json::json!({
+///     "object": "edit", "created": 0, "choices": [{ "text": "What day of the week is it?", "index": 0 }]
+/// })).header("openai-organization", "org-secret").header("set-cookie", "session=secret"));
+///
+/// let edit = Edit { input: Cow::Borrowed("What day of the wek is it?"), ..Default::default() };
+///
+/// // Recording sends the request through the transport and writes it to the cassette,
+/// // redacting the credentials and the default headers.
+/// let client = openai::builder("sk-secret")
+///     .organization("org-secret")
+///     .default_header("OpenAI-Project", "proj-secret")
+///     .default_header("X-Gateway-Key", "gateway-secret")
+///     .transport(transport)
+///     .cassette(&path, CassetteMode::Record)
+///     .build()
+///     .unwrap();
+/// client.create(Some("text-davinci-edit-001"), &edit).await.unwrap();
+/// let cassette = std::fs::read_to_string(&path).unwrap();
+/// assert!(!cassette.contains("secret"));
+/// let cassette = Cassette::load(&path).unwrap();
+/// let headers = &cassette.interactions[0].response.headers;
+/// assert!(headers.contains(&("openai-organization".to_owned(), REDACTED.to_owned())));
+/// assert!(!headers.iter().any(|(name, _)| name == "set-cookie"));
+///
+/// // Replaying serves the same request from the cassette.
+/// let client = openai::builder("api_key").cassette(&path, CassetteMode::Replay).build().unwrap();
+/// let response = client.create(Some("text-davinci-edit-001"), &edit).await.unwrap();
+/// assert_eq!(response.choices[0].text, "What day of the week is it?");
+///
+/// // A request which was not recorded fails.
+/// let other = Edit { input: Cow::Borrowed("Hello"), ..Default::default() };
+/// assert!(client.create(Some("text-davinci-edit-001"), &other).await.is_err());
+/// # std::fs::remove_file(&path).unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ReplayTransport {
+    source: String,
+    interactions: Mutex<Vec<Option<Interaction>>>,
+}
+
+impl ReplayTransport {
+    /// Returns a transport which serves the interactions of the cassette.
+    pub fn new(cassette: Cassette) -> Self {
+        Self::with_source(cassette, "cassette".to_owned())
+    }
+
+    /// Returns a transport which serves the interactions of the cassette file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ResponseError> {
+        let path = path.as_ref();
+        Ok(Self::with_source(Cassette::load(path)?, format!("cassette {}", path.display())))
+    }
+
+    fn with_source(cassette: Cassette, source: String) -> Self {
+        Self {
+            source,
+            interactions: Mutex::new(cassette.interactions.into_iter().map(Some).collect()),
+        }
+    }
+
+    /// Returns the number of interactions which were not served yet.
+    pub fn remaining(&self) -> usize {
+        self.interactions.lock().unwrap().iter().flatten().count()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: Request<Body>) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            // The headers are not matched, so none have to be redacted.
+            let request = CassetteRequest::new(&parts, &body, &[]);
+
+            let mut interactions = self.interactions.lock().unwrap();
+            let interaction = interactions.iter_mut()
+                .find(|interaction| interaction.as_ref().is_some_and(|interaction| interaction.request.matches(&request)))
+                .and_then(Option::take);
+
+            match interaction {
+                Some(interaction) => interaction.response.to_response(),
+                None => {
+                    let message = format!(
+                        "No interaction of the {} matches the request {} {} with the body {:?}",
+                        self.source, request.method, request.uri, request.body
+                    );
+                    error!("{}", message);
+                    Err(ResponseError::Transport(message.into()))
+                }
+            }
+        })
+    }
+}
+
+/// Returns the transport of the cassette in the mode, wrapping the given transport to record.
+/// The named headers are redacted in addition to the [`REDACTED_HEADERS`].
+pub(crate) fn cassette_transport(
+    inner: Arc<dyn Transport>,
+    path: &Path,
+    mode: CassetteMode,
+    redacted: &[String]
+) -> Result<Arc<dyn Transport>, ResponseError> {
+    let replay = match mode {
+        CassetteMode::Record => false,
+        CassetteMode::Replay => true,
+        CassetteMode::Once => path.exists(),
+    };
+
+    if replay {
+        Ok(Arc::new(ReplayTransport::from_file(path)?))
+    } else {
+        let transport = redacted.iter()
+            .fold(RecordingTransport::with_shared(inner, path), |transport, name| transport.redact_header(name));
+        Ok(Arc::new(transport))
+    }
+}
+
+/// Returns the headers as pairs of name and value, skipping values which are not valid UTF-8.
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers.iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+        .collect()
+}
+
+/// Replaces the values of the named headers, which are lowercase, with [`REDACTED`]
+/// and drops the [`DROPPED_HEADERS`].
+fn redact(headers: Vec<(String, String)>, redacted: &[String]) -> Vec<(String, String)> {
+    headers.into_iter()
+        .filter(|(name, _)| !DROPPED_HEADERS.contains(&name.as_str()))
+        .map(|(name, value)| if redacted.contains(&name) { (name, REDACTED.to_owned()) } else { (name, value) })
+        .collect()
+}
+
+/// Replaces all occurrences of the pattern in the bytes.
+fn replace(bytes: &[u8], pattern: &[u8], replacement: &[u8]) -> Vec<u8> {
+    let mut replaced = Vec::with_capacity(bytes.len());
+    let mut rest = bytes;
+    while let Some(position) = rest.windows(pattern.len()).position(|window| window == pattern) {
+        replaced.extend_from_slice(&rest[..position]);
+        replaced.extend_from_slice(replacement);
+        rest = &rest[position + pattern.len()..];
+    }
+
+    replaced.extend_from_slice(rest);
+    replaced
+}
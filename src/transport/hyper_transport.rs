@@ -0,0 +1,24 @@
+use hyper::client::HttpConnector;
+use hyper::{Body, Request};
+use hyper_openssl::HttpsConnector;
+use crate::transport::{Transport, TransportFuture};
+
+/// The default transport, which sends requests with hyper over OpenSSL.
+/// It is configured by the connection options of the [`ClientBuilder`](crate::client::ClientBuilder).
+#[derive(Debug, Clone)]
+pub struct HyperTransport {
+    client: hyper::Client<HttpsConnector<HttpConnector>>,
+}
+
+impl HyperTransport {
+    /// Returns a transport which sends its requests with the given hyper client.
+    pub fn new(client: hyper::Client<HttpsConnector<HttpConnector>>) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for HyperTransport {
+    fn send(&self, request: Request<Body>) -> TransportFuture<'_> {
+        Box::pin(async move { Ok(self.client.request(request).await?) })
+    }
+}
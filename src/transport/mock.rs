@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use hyper::body::Bytes;
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper::{Body, HeaderMap, Method, Request, Response, StatusCode, Uri};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::endpoints::ResponseError;
+use crate::transport::{Transport, TransportFuture};
+
+/// An in-memory transport which records the requests and answers with scripted responses.
+///
+/// The transport is a handle to shared state, so a clone can be kept to inspect the
+/// requests after the client took ownership of the other one.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use hyper::StatusCode;
+/// use openai_rs::endpoints::edits::Edit;
+/// use openai_rs::openai;
+/// use openai_rs::transport::{MockResponse, MockTransport};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let transport = MockTransport::new();
+/// transport.push(MockResponse::json(StatusCode::OK, &serde_json::json!({
+///     "object": "edit",
+///     "created": 1589478378,
+///     "choices": [{ "text": "What day of the week is it?", "index": 0 }]
+/// })));
+///
+/// let client = openai::builder("api_key")
+///     .transport(transport.clone())
+///     .build()
+///     .unwrap();
+///
+/// let edit = Edit { input: Cow::Borrowed("What day of the wek is it?"), ..Default::default() };
+/// let response = client.create(Some("text-davinci-edit-001"), &edit).await.unwrap();
+/// assert_eq!(response.choices[0].text, "What day of the week is it?");
+///
+/// let requests = transport.requests();
+/// assert_eq!(requests[0].uri, "https://api.openai.com/v1/engines/text-davinci-edit-001/edits");
+/// let body: serde_json::Value = requests[0].json().unwrap();
+/// assert_eq!(body["input"], "What day of the wek is it?");
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    requests: Vec<RecordedRequest>,
+    responses: VecDeque<MockResponse>,
+}
+
+/// A request which was sent through the [`MockTransport`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub uri: Uri,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+/// A scripted response of the [`MockTransport`].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl MockTransport {
+    /// Returns a transport without scripted responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a response, which is returned after all previously pushed ones.
+    pub fn push(&self, response: MockResponse) -> &Self {
+        self.state.lock().unwrap().responses.push_back(response);
+        self
+    }
+
+    /// Returns the requests which were sent so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Returns the number of scripted responses which were not returned yet.
+    pub fn remaining(&self) -> usize {
+        self.state.lock().unwrap().responses.len()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: Request<Body>) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+
+            let mut state = self.state.lock().unwrap();
+            state.requests.push(RecordedRequest {
+                method: parts.method,
+                uri: parts.uri,
+                headers: parts.headers,
+                body,
+            });
+
+            let scripted = state.responses.pop_front().ok_or_else(|| {
+                ResponseError::Transport("MockTransport has no scripted response left".into())
+            })?;
+
+            let mut response = Response::new(Body::from(scripted.body));
+            *response.status_mut() = scripted.status;
+            *response.headers_mut() = scripted.headers;
+            Ok(response)
+        })
+    }
+}
+
+impl RecordedRequest {
+    /// Deserializes the JSON body of the request.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+impl MockResponse {
+    /// Returns a response with the given status and raw body.
+    pub fn new(status: StatusCode, body: impl Into<Bytes>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Returns a response with the given status and the value serialized as JSON body.
+    pub fn json<T: Serialize>(status: StatusCode, value: &T) -> Self {
+        let body = serde_json::to_vec(value).expect("Failed to serialize MockResponse");
+        Self::new(status, body).header(CONTENT_TYPE.as_str(), "application/json")
+    }
+
+    /// Returns a successful response streaming the given data as server-sent events,
+    /// terminated by the `[DONE]` event.
+    pub fn sse<T: Serialize>(chunks: &[T]) -> Self {
+        let mut body = String::new();
+        for chunk in chunks {
+            let data = serde_json::to_string(chunk).expect("Failed to serialize MockResponse");
+            body.push_str(&format!("data: {}\n\n", data));
+        }
+        body.push_str("data: [DONE]\n\n");
+
+        Self::new(StatusCode::OK, body).header(CONTENT_TYPE.as_str(), "text/event-stream")
+    }
+
+    /// Adds a header to the response.
+    ///
+    /// # Panics
+    /// Panics if the name or the value is not a valid header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.append(
+            HeaderName::from_bytes(name.as_bytes()).expect("Invalid header name"),
+            HeaderValue::from_str(value).expect("Invalid header value")
+        );
+        self
+    }
+}
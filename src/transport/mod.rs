@@ -0,0 +1,38 @@
+//! The HTTP layer the [`Client`](crate::client::Client) sends its requests through.
+//!
+//! By default requests are sent by the [`HyperTransport`], which uses hyper with OpenSSL.
+//! A different HTTP stack can be plugged in by implementing [`Transport`], and the
+//! [`MockTransport`] allows to test code using the client without network access.
+//! The [`RecordingTransport`] records the requests of a client with their responses to a
+//! cassette file, which the [`ReplayTransport`] serves in tests instead of the API.
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use hyper::{Body, Request, Response};
+use crate::endpoints::ResponseError;
+
+mod cassette;
+mod hyper_transport;
+mod mock;
+
+pub use cassette::{
+    Cassette, CassetteBody, CassetteMode, CassetteRequest, CassetteResponse, Interaction,
+    RecordingTransport, ReplayTransport, REDACTED, REDACTED_HEADERS
+};
+pub(crate) use cassette::cassette_transport;
+pub use hyper_transport::HyperTransport;
+pub use mock::{MockResponse, MockTransport, RecordedRequest};
+
+/// The future of a response sent by a [`Transport`].
+pub type TransportFuture<'a> = Pin<Box<
+    dyn Future<Output = Result<Response<Body>, ResponseError>> + Send + 'a
+>>;
+
+/// A transport which sends a request and returns the response, without interpreting its status.
+///
+/// Timeouts, retries and error handling of the client are applied on top of the transport.
+pub trait Transport: Debug + Send + Sync {
+    /// Sends the request and returns the response once its head has been received.
+    fn send(&self, request: Request<Body>) -> TransportFuture<'_>;
+}
@@ -0,0 +1,100 @@
+//! Cumulative token usage of a client, counted per tenant.
+//!
+//! Every response which reports its [`Usage`] is added to the counter of the tenant
+//! of the [`Client`](crate::client::Client) which sent the request. Clients derived with
+//! [`Client::with_tenant`](crate::client::Client::with_tenant) share the same tracker.
+//!
+//! # Example
+//! ```
+//! use openai_rs::client::Client;
+//! use openai_rs::openai;
+//!
+//! let client: Client = openai::new("api_key");
+//! let search_team = client.with_tenant("search");
+//!
+//! // ... send requests with `search_team` ...
+//!
+//! let usage = client.usage().get("search");
+//! println!("search used {} tokens", usage.total_tokens);
+//!
+//! // Start the next billing period.
+//! client.usage().reset("search");
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::endpoints::Usage;
+
+/// The tenant of a client which was not assigned to one.
+pub const DEFAULT_TENANT: &str = "default";
+
+/// A thread-safe counter of the cumulative token usage per tenant.
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    tenants: Mutex<HashMap<String, Usage>>,
+}
+
+impl UsageTracker {
+    /// Adds the usage to the counter of the tenant.
+    ///
+    /// # Example
+    /// ```
+    /// use openai_rs::endpoints::Usage;
+    /// use openai_rs::usage::UsageTracker;
+    ///
+    /// let tracker = UsageTracker::default();
+    /// let usage = Usage {
+    ///     prompt_tokens: 3,
+    ///     completion_tokens: 2,
+    ///     total_tokens: 5,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// tracker.record("search", &usage);
+    /// tracker.record("search", &usage);
+    /// tracker.record("support", &usage);
+    ///
+    /// assert_eq!(tracker.get("search").total_tokens, 10);
+    /// assert_eq!(tracker.total().total_tokens, 15);
+    /// assert_eq!(tracker.reset("search").total_tokens, 10);
+    /// assert_eq!(tracker.get("search"), Usage::default());
+    /// ```
+    pub fn record(&self, tenant: &str, usage: &Usage) {
+        let mut tenants = self.tenants.lock().unwrap();
+        match tenants.get_mut(tenant) {
+            Some(total) => *total += *usage,
+            None => {
+                tenants.insert(tenant.to_owned(), *usage);
+            }
+        }
+    }
+
+    /// Returns the cumulative usage of the tenant.
+    pub fn get(&self, tenant: &str) -> Usage {
+        self.tenants.lock().unwrap().get(tenant).copied().unwrap_or_default()
+    }
+
+    /// Returns the cumulative usage of all tenants.
+    pub fn total(&self) -> Usage {
+        self.tenants.lock().unwrap().values()
+            .fold(Usage::default(), |mut total, usage| {
+                total += *usage;
+                total
+            })
+    }
+
+    /// Returns the cumulative usage of each tenant.
+    pub fn snapshot(&self) -> HashMap<String, Usage> {
+        self.tenants.lock().unwrap().clone()
+    }
+
+    /// Resets the counter of the tenant and returns its usage until now.
+    pub fn reset(&self, tenant: &str) -> Usage {
+        self.tenants.lock().unwrap().remove(tenant).unwrap_or_default()
+    }
+
+    /// Resets the counters of all tenants and returns their usage until now.
+    pub fn reset_all(&self) -> HashMap<String, Usage> {
+        std::mem::take(&mut *self.tenants.lock().unwrap())
+    }
+}